@@ -14,7 +14,11 @@ use seventh_day::*;
 use sixth_day::*;
 use third_day::*;
 
-use super::lines_from_file;
+use std::convert::TryFrom;
+use std::fs::File;
+use std::io::BufReader;
+
+use super::{lines_from_file, write_output};
 
 pub fn run_first_week() {
     run_first_day();
@@ -28,22 +32,121 @@ pub fn run_first_week() {
 
 fn run_first_day() {
     let list = lines_from_file("inputs/first_week/day1.txt").expect("Could not load lines");
-    let input = list
-        .into_iter()
-        .map(|s| s.parse::<i32>().unwrap())
-        .collect::<Vec<i32>>();
-    let result = expenses(input.clone());
-    println!("Result of first multiplication is: {}", result);
-    let result = expenses2(input);
-    println!("Result of second multiplication is: {}", result);
+    // The same report read as amounts of money, in cents
+    let cents = parse_cent_expenses::<i64>(list.clone()).expect("Could not parse amounts");
+    let input = parse_expenses::<i64>(list).expect("Could not parse expenses");
+    match expenses(input.clone()) {
+        Ok(result) => println!("Result of first multiplication is: {}", result),
+        Err(ExpenseError::NoMatch) => {
+            for tie in [Tie::Under, Tie::Over] {
+                print_nearest(&input, 2, tie);
+            }
+        }
+        Err(e) => println!("Could not multiply the pair: {:?}", e),
+    }
+    match expenses2(input.clone()) {
+        Ok(result) => println!("Result of second multiplication is: {}", result),
+        Err(ExpenseError::NoMatch) => {
+            for tie in [Tie::Under, Tie::Over] {
+                print_nearest(&input, 3, tie);
+            }
+        }
+        Err(e) => println!("Could not multiply the triple: {:?}", e),
+    }
+    let result = k_sums(&input, 3, 2020, Selection::Distinct).count();
+    println!("Triples of expenses adding up to 2020: {}", result);
+    match find_k_sum(&cents, 2, 202_000) {
+        Some(pair) => println!(
+            "Amounts adding up to 2020.00: {}",
+            pair.entries
+                .iter()
+                .map(|c| format!("{}.{:02}", c / 100, c % 100))
+                .collect::<Vec<String>>()
+                .join(" and ")
+        ),
+        None => println!("No two amounts add up to 2020.00"),
+    }
+    let options = SubsetOptions {
+        max_size: None,
+        smallest: true,
+    };
+    match subset_sum(&input, 2020, options) {
+        Ok(subset) => println!(
+            "Smallest set of expenses adding up to 2020 has {} entries",
+            subset.entries.len()
+        ),
+        Err(ExpenseError::NoMatch) => println!("No set of expenses adds up to 2020"),
+        Err(e) => println!("Could not search the sets of expenses: {:?}", e),
+    }
+}
+
+// Closest `k` expenses to 2020, `tie` picks the side when both are as close.
+fn print_nearest(input: &[i64], k: usize, tie: Tie) {
+    match nearest_k_sum(input, k, 2020, tie) {
+        Some(nearest) => println!(
+            "No {} expenses add up to 2020, closest ({:?} on ties) are {:?} adding up to {}",
+            k,
+            tie,
+            nearest.entries,
+            nearest.entries.iter().sum::<i64>()
+        ),
+        None => println!("Not enough expenses to pick {}", k),
+    }
 }
 
 fn run_second_day() {
+    let file = File::open("inputs/first_week/day2.txt").expect("Could not open file");
+    match scan_passwords(BufReader::new(file), Segmentation::Chars, 4) {
+        Ok(counts) => {
+            println!("Incorrect passwords: {}", counts.count_valid);
+            println!("Correct passwords: {}", counts.position_valid);
+        }
+        Err(ScanError::Io(e)) => println!("Could not read the password list: {}", e),
+        Err(ScanError::Policy(e)) => println!("Could not parse the password policies: {:?}", e),
+    }
+    // The reports below need every line at once, they share a single load of the list.
     let list = lines_from_file("inputs/first_week/day2.txt").expect("Could not load lines");
-    let result = incorrect_passwords(list.clone());
-    println!("Incorrect passwords: {}", result);
-    let result = correct_passwords(list);
-    println!("Correct passwords: {}", result);
+    match Audit::new(&list, Segmentation::Chars) {
+        Ok(audit) => {
+            println!("Password audit: {}", audit.summary());
+            for (format, filename) in [
+                (AuditFormat::Csv, "outputs/first_week/day2_audit.csv"),
+                (AuditFormat::Json, "outputs/first_week/day2_audit.json"),
+            ] {
+                match write_output(filename, &audit.export(format)) {
+                    Ok(()) => println!("Password audit written to {}", filename),
+                    Err(e) => println!("Could not write the password audit: {}", e),
+                }
+            }
+        }
+        Err(e) => println!("Could not parse the password policies: {:?}", e),
+    }
+    for (name, rule) in [
+        ("count", &CountRange as &dyn RepairableRule),
+        ("position", &ExactlyOnePosition),
+    ] {
+        match repairs(&list, rule, Segmentation::Chars) {
+            Ok(repairs) => println!(
+                "Edits to repair the passwords failing the {} policy: {}",
+                name,
+                repairs
+                    .iter()
+                    .flat_map(|(_, r)| r)
+                    .map(|r| r.edits)
+                    .sum::<usize>()
+            ),
+            Err(e) => println!("Could not parse the password policies: {:?}", e),
+        }
+    }
+    let config = lines_from_file("inputs/first_week/day2_rules.txt").expect("Could not load lines");
+    let rules = match RuleSet::from_config(&config) {
+        Ok(rules) => rules,
+        Err(e) => return println!("Could not read the password rules: {:?}", e),
+    };
+    match valid_passwords(&list, &rules, Segmentation::Graphemes) {
+        Ok(result) => println!("Passwords following the configured rules: {}", result),
+        Err(e) => println!("Could not parse the password policies: {:?}", e),
+    }
 }
 
 fn run_third_day() {
@@ -51,8 +154,46 @@ fn run_third_day() {
     let result = tree_map(input.clone(), 1, 3);
     println!("Number of trees: {}", result);
     let attempts: Vec<(usize, usize)> = vec![(1, 1), (1, 3), (1, 5), (1, 7), (2, 1)];
-    let result = check_slots(input, attempts);
+    let slopes: Vec<Slope> = attempts
+        .iter()
+        .map(|pair| Slope::try_from(*pair).expect("Invalid slope"))
+        .collect();
+    let result = check_slots(input.clone(), attempts);
     println!("Number of tree posibilities: {}", result);
+    for (filename, contents) in [
+        (
+            "outputs/first_week/day3_path.txt",
+            render_path(&input, Slope::new(3, 1), (0, 0)),
+        ),
+        (
+            "outputs/first_week/day3_slopes.svg",
+            render_svg(&input, &slopes),
+        ),
+    ] {
+        let contents = match contents {
+            Some(contents) => contents,
+            None => {
+                println!("The toboggan paths are too wide to draw");
+                continue;
+            }
+        };
+        match write_output(filename, &contents) {
+            Ok(()) => println!("Toboggan paths drawn in {}", filename),
+            Err(e) => println!("Could not draw the toboggan paths: {}", e),
+        }
+    }
+    let slope = Slope::rational((1, 2), (1, 1)).expect("Invalid slope");
+    let result = ride(&input, slope, (0, 0));
+    println!(
+        "Trees going half a column right per row: {} in {} cells",
+        result.trees,
+        result.visited.len()
+    );
+    let search = search_slopes(&input, 1..=7, 1..=2);
+    println!(
+        "Slopes with the fewest trees: {:?}, with the most: {:?}",
+        search.fewest, search.most
+    );
 }
 
 fn run_fourth_day() {
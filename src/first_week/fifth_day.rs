@@ -11,7 +11,7 @@ fn find_seat_ids(input: Vec<String>) -> Vec<usize> {
             let mut column = columns.clone();
             let row_mov = s.get(0..7).unwrap();
             let column_mov = s.get(7..).unwrap();
-            row_mov.chars().into_iter().for_each(|m| {
+            row_mov.chars().for_each(|m| {
                 let (head, tail) = row.split_at(row.len() / 2);
                 if m == 'F' {
                     row = head.to_vec();
@@ -19,7 +19,7 @@ fn find_seat_ids(input: Vec<String>) -> Vec<usize> {
                     row = tail.to_vec();
                 }
             });
-            column_mov.chars().into_iter().for_each(|m| {
+            column_mov.chars().for_each(|m| {
                 let (head, tail) = column.split_at(column.len() / 2);
                 if m == 'L' {
                     column = head.to_vec();
@@ -27,7 +27,7 @@ fn find_seat_ids(input: Vec<String>) -> Vec<usize> {
                     column = tail.to_vec();
                 }
             });
-            (*row.get(0).unwrap(), *column.get(0).unwrap())
+            (*row.first().unwrap(), *column.first().unwrap())
         })
         // Get ID
         .map(|(r, c)| (r * 8) + c)
@@ -1,41 +1,900 @@
-use std::collections::HashSet;
-use std::iter::FromIterator;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, VecDeque};
+use std::convert::{TryFrom, TryInto};
+use std::fmt;
+use std::ops::Range;
+use std::str::FromStr;
 
-pub(super) fn expenses(input: Vec<i32>) -> i32 {
-    let objective = 2020;
-    let set: HashSet<i32> = HashSet::from_iter(input.iter().cloned());
-    for value in input {
-        let key = objective - value;
-        if let Some(result) = set.get(&key) {
-            return result * value;
+/// Integer type the expense report can be kept in, sums are worked out as `i128`.
+pub(super) trait Amount:
+    Copy
+    + Ord
+    + fmt::Debug
+    + fmt::Display
+    + FromStr
+    + From<u16>
+    + Into<i128>
+    + TryFrom<i128>
+    + TryInto<usize>
+{
+    fn checked_mul(self, other: Self) -> Option<Self>;
+
+    fn zero() -> Self {
+        Self::from(0)
+    }
+}
+
+macro_rules! impl_amount {
+    ($($t:ty),*) => {
+        $(impl Amount for $t {
+            fn checked_mul(self, other: Self) -> Option<Self> {
+                <$t>::checked_mul(self, other)
+            }
+        })*
+    };
+}
+
+impl_amount!(i32, i64, i128, u64);
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(super) enum ExpenseError {
+    NoMatch,
+    Overflow,
+    Parse(String),
+    /// The program of `subset_sum` would need more memory than it is allowed.
+    TooLarge,
+}
+
+/// Entries of the report that add up to a target, with their positions in the report.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(super) struct Combination<T> {
+    pub indices: Vec<usize>,
+    pub entries: Vec<T>,
+}
+
+impl<T: Amount> Combination<T> {
+    fn new(sorted: &[(T, usize)], chosen: &[usize]) -> Self {
+        let mut picked: Vec<(T, usize)> = chosen.iter().map(|c| sorted[*c]).collect();
+        picked.sort_unstable_by_key(|(_, i)| *i);
+        Self {
+            indices: picked.iter().map(|(_, i)| *i).collect(),
+            entries: picked.iter().map(|(v, _)| *v).collect(),
+        }
+    }
+
+    /// Product of the entries, failing instead of wrapping around when it overflows.
+    pub fn product(&self) -> Result<T, ExpenseError> {
+        self.entries
+            .iter()
+            .try_fold(T::from(1), |product, e| product.checked_mul(*e))
+            .ok_or(ExpenseError::Overflow)
+    }
+}
+
+/// Whether a combination may use the same entry of the report more than once.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub(super) enum Selection {
+    Distinct,
+    WithReplacement,
+}
+
+// Entries paired with their index in the report, sorted by value.
+fn sort_entries<T: Amount>(entries: &[T]) -> Vec<(T, usize)> {
+    let mut sorted: Vec<(T, usize)> = entries.iter().cloned().zip(0..).collect();
+    sorted.sort_unstable();
+    sorted
+}
+
+// Positions of the last entries that complete a combination, equal values grouped together.
+enum Run {
+    // Nothing left to pick.
+    Empty,
+    // One position from the range.
+    Single(Range<usize>),
+    // One position from each range.
+    Cross(Range<usize>, Range<usize>),
+    // Two positions from the same range of equal values.
+    Within(Range<usize>),
+}
+
+impl Run {
+    fn count(&self, reuse: bool) -> usize {
+        match self {
+            Run::Empty => 1,
+            Run::Single(r) => r.len(),
+            Run::Cross(a, b) => a.len() * b.len(),
+            Run::Within(r) if reuse => r.len() * (r.len() + 1) / 2,
+            Run::Within(r) => r.len() * (r.len() - 1) / 2,
+        }
+    }
+
+    fn positions(&self, reuse: bool) -> Vec<Vec<usize>> {
+        match self {
+            Run::Empty => vec![Vec::new()],
+            Run::Single(r) => r.clone().map(|i| vec![i]).collect(),
+            Run::Cross(a, b) => a
+                .clone()
+                .flat_map(|i| b.clone().map(move |j| vec![i, j]))
+                .collect(),
+            Run::Within(r) => {
+                let end = r.end;
+                r.clone()
+                    .flat_map(|i| {
+                        let from = if reuse { i } else { i + 1 };
+                        (from..end).map(move |j| vec![i, j])
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+/// Every combination of `k` entries adding up to a target, found lazily.
+///
+/// The first `k - 2` entries are walked like an odometer over the sorted report and
+/// the last two close the gap with two pointers. Equal values are still different
+/// entries, so each one shows up in its own combination.
+pub(super) struct KSums<T> {
+    sorted: Vec<(T, usize)>,
+    inner: usize,
+    target: T,
+    reuse: bool,
+    prefix: Vec<usize>,
+    started: bool,
+    pending: VecDeque<Vec<usize>>,
+}
+
+impl<T: Amount> KSums<T> {
+    // `inner` of the `k` entries are left out of the odometer for the caller to pick.
+    fn new(entries: &[T], k: usize, inner: usize, target: T, selection: Selection) -> Self {
+        Self {
+            sorted: sort_entries(entries),
+            inner,
+            target,
+            reuse: selection == Selection::WithReplacement,
+            prefix: vec![0; k - inner],
+            started: false,
+            pending: VecDeque::new(),
+        }
+    }
+
+    // Moves the prefix to the next set of positions, false once all were visited.
+    fn advance(&mut self) -> bool {
+        let size = self.sorted.len();
+        let step = if self.reuse { 0 } else { 1 };
+        let len = self.prefix.len();
+        let mut level;
+        if !self.started {
+            self.started = true;
+            if len == 0 {
+                return true;
+            }
+            self.prefix[0] = 0;
+            level = 0;
+        } else {
+            if len == 0 {
+                return false;
+            }
+            level = len - 1;
+            self.prefix[level] += 1;
+        }
+        loop {
+            for l in level + 1..len {
+                self.prefix[l] = self.prefix[l - 1] + step;
+            }
+            if self.prefix[len - 1] < size {
+                return true;
+            }
+            if level == 0 {
+                return false;
+            }
+            level -= 1;
+            self.prefix[level] += 1;
+        }
+    }
+
+    // First position the entries after the prefix can take.
+    fn start(&self) -> usize {
+        match self.prefix.last() {
+            Some(p) if self.reuse => *p,
+            Some(p) => p + 1,
+            None => 0,
+        }
+    }
+
+    // What the entries after the prefix have to add up to.
+    // Gaps beyond `i128` only come up with `i128` entries.
+    fn remaining(&self) -> Option<i128> {
+        self.prefix
+            .iter()
+            .try_fold(self.target.into(), |r: i128, p| {
+                r.checked_sub(self.sorted[*p].0.into())
+            })
+    }
+
+    fn runs(&self) -> Vec<Run> {
+        let sorted = &self.sorted;
+        let start = self.start();
+        let remaining = match self.remaining() {
+            Some(r) => r,
+            None => return Vec::new(),
+        };
+        if self.inner == 0 {
+            return if remaining == 0 {
+                vec![Run::Empty]
+            } else {
+                Vec::new()
+            };
+        }
+        if start >= sorted.len() {
+            return Vec::new();
+        }
+        if self.inner == 1 {
+            let rest = &sorted[start..];
+            let low = start + rest.partition_point(|(v, _)| (*v).into() < remaining);
+            let high = start + rest.partition_point(|(v, _)| (*v).into() <= remaining);
+            return if low < high {
+                vec![Run::Single(low..high)]
+            } else {
+                Vec::new()
+            };
+        }
+        let mut runs = Vec::new();
+        let (mut low, mut high) = (start, sorted.len() - 1);
+        while low < high || (self.reuse && low == high) {
+            let (a, b) = (sorted[low].0, sorted[high].0);
+            // An overflowing sum is past the remaining gap in the direction of its sign.
+            let ordering = match a.into().checked_add(b.into()) {
+                Some(sum) => sum.cmp(&remaining),
+                None if a > T::zero() => Ordering::Greater,
+                None => Ordering::Less,
+            };
+            match ordering {
+                Ordering::Less => low += 1,
+                Ordering::Greater => {
+                    if high == 0 {
+                        break;
+                    }
+                    high -= 1;
+                }
+                Ordering::Equal if a == b => {
+                    runs.push(Run::Within(low..high + 1));
+                    break;
+                }
+                Ordering::Equal => {
+                    let low_end = low + sorted[low..].partition_point(|(v, _)| *v == a);
+                    let high_start = sorted[..=high].partition_point(|(v, _)| *v < b);
+                    runs.push(Run::Cross(low..low_end, high_start..high + 1));
+                    low = low_end;
+                    if high_start == 0 {
+                        break;
+                    }
+                    high = high_start - 1;
+                }
+            }
+        }
+        runs
+    }
+
+    /// Number of combinations left, without building them.
+    pub fn count(mut self) -> usize {
+        let mut total = self.pending.len();
+        while self.advance() {
+            total += self
+                .runs()
+                .iter()
+                .map(|r| r.count(self.reuse))
+                .sum::<usize>();
+        }
+        total
+    }
+}
+
+impl<T: Amount> Iterator for KSums<T> {
+    type Item = Combination<T>;
+
+    fn next(&mut self) -> Option<Combination<T>> {
+        loop {
+            if let Some(inner) = self.pending.pop_front() {
+                let chosen: Vec<usize> = self.prefix.iter().cloned().chain(inner).collect();
+                return Some(Combination::new(&self.sorted, &chosen));
+            }
+            if !self.advance() {
+                return None;
+            }
+            let reuse = self.reuse;
+            self.pending = self
+                .runs()
+                .iter()
+                .flat_map(|r| r.positions(reuse))
+                .collect();
+        }
+    }
+}
+
+/// Every combination of `k` entries that adds up to `target`.
+pub(super) fn k_sums<T: Amount>(
+    entries: &[T],
+    k: usize,
+    target: T,
+    selection: Selection,
+) -> KSums<T> {
+    KSums::new(entries, k, k.min(2), target, selection)
+}
+
+/// First combination of `k` different entries that adds up to `target`.
+pub(super) fn find_k_sum<T: Amount>(entries: &[T], k: usize, target: T) -> Option<Combination<T>> {
+    find_k_sum_with(entries, k, target, Selection::Distinct)
+}
+
+pub(super) fn find_k_sum_with<T: Amount>(
+    entries: &[T],
+    k: usize,
+    target: T,
+    selection: Selection,
+) -> Option<Combination<T>> {
+    k_sums(entries, k, target, selection).next()
+}
+
+/// Which side of the target wins when two sums are just as far from it.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub(super) enum Tie {
+    Under,
+    Over,
+}
+
+// Distance of a sum to the target, then the side it falls on, smaller is closer.
+fn closeness(sum: i128, target: i128, tie: Tie) -> (u128, bool) {
+    let over = sum > target;
+    let under = sum < target;
+    let losing = match tie {
+        Tie::Under => over,
+        Tie::Over => under,
+    };
+    (sum.abs_diff(target), losing)
+}
+
+/// The `n` combinations of `k` different entries closest to `target`, closest first.
+///
+/// Every set of the first `k - 1` entries is tried, with the last entry walking away
+/// from the gap on both sides until it is further than the worst combination kept.
+/// Pairs take a binary search per entry, so large reports are fine.
+pub(super) fn nearest_k_sums<T: Amount>(
+    entries: &[T],
+    k: usize,
+    target: T,
+    tie: Tie,
+    n: usize,
+) -> Vec<Combination<T>> {
+    if n == 0 {
+        return Vec::new();
+    }
+    let mut walk = KSums::new(entries, k, k.min(1), target, Selection::Distinct);
+    let target: i128 = target.into();
+    let mut best: BinaryHeap<((u128, bool), Vec<usize>)> = BinaryHeap::new();
+    // Keeps the candidate if it is among the closest, false once it is not.
+    let mut offer = |key: (u128, bool), chosen: Vec<usize>| {
+        if best.len() == n {
+            match best.peek() {
+                Some((worst, _)) if key.0 > worst.0 => return false,
+                _ => {}
+            }
+        }
+        best.push((key, chosen));
+        if best.len() > n {
+            best.pop();
+        }
+        true
+    };
+    while walk.advance() {
+        let remaining = match walk.remaining() {
+            Some(r) => r,
+            None => continue,
+        };
+        let prefix = walk.prefix.clone();
+        if walk.inner == 0 {
+            offer(closeness(target - remaining, target, tie), prefix);
+            continue;
+        }
+        let start = walk.start();
+        let rest = &walk.sorted[start.min(walk.sorted.len())..];
+        let split = start + rest.partition_point(|(v, _)| (*v).into() < remaining);
+        let with = |i: usize| {
+            prefix
+                .iter()
+                .cloned()
+                .chain(Some(i))
+                .collect::<Vec<usize>>()
+        };
+        let sum = |i: usize| target - remaining + walk.sorted[i].0.into();
+        for i in (start..split).rev() {
+            if !offer(closeness(sum(i), target, tie), with(i)) {
+                break;
+            }
+        }
+        for i in split..walk.sorted.len() {
+            if !offer(closeness(sum(i), target, tie), with(i)) {
+                break;
+            }
+        }
+    }
+    best.into_sorted_vec()
+        .into_iter()
+        .map(|(_, chosen)| Combination::new(&walk.sorted, &chosen))
+        .collect()
+}
+
+/// Combination of `k` different entries with the sum closest to `target`.
+pub(super) fn nearest_k_sum<T: Amount>(
+    entries: &[T],
+    k: usize,
+    target: T,
+    tie: Tie,
+) -> Option<Combination<T>> {
+    nearest_k_sums(entries, k, target, tie, 1).pop()
+}
+
+/// Limits for `subset_sum`, by default any subset size is fine.
+#[derive(Debug, Clone, Copy, Default)]
+pub(super) struct SubsetOptions {
+    pub max_size: Option<usize>,
+    pub smallest: bool,
+}
+
+// Walks back the items that last lowered the count of each sum.
+fn smallest_subset(sizes: &[usize], taken: &[Vec<u64>], target: usize) -> Vec<usize> {
+    let mut sum = target;
+    let mut chosen = Vec::new();
+    for (i, size) in sizes.iter().enumerate().rev() {
+        if sum == 0 {
+            break;
+        }
+        if taken[i][sum / 64] & (1 << (sum % 64)) != 0 {
+            chosen.push(i);
+            sum -= size;
         }
     }
-    0
+    chosen
 }
 
-pub(super) fn expenses2(input: Vec<i32>) -> i32 {
-    let objective = 2020;
-    let set: HashSet<i32> = HashSet::from_iter(input.iter().cloned());
-    for value in &input {
-        let new_objective: i32 = objective - value;
-        for v in &input {
-            let key: i32 = new_objective - v;
-            if let Some(result) = set.get(&key) {
-                return result * value * v;
+// Most sums `subset_sum` runs its program over, and most words of bits the smallest
+// subset may keep, around 100 MB each.
+const SUBSET_MAX_SUMS: usize = 1 << 22;
+const SUBSET_MAX_WORDS: usize = 1 << 24;
+
+/// Set of entries of any size adding up to `target`, only positive entries are used.
+///
+/// Runs a dynamic program over every sum up to the target, ignoring sums that need more
+/// entries than the size cap. Unless the smallest subset is asked for, the program stops
+/// at the first subset reached, falling back to the smallest one when the first breaks
+/// the cap. Only the smallest subset keeps a bit per entry and sum to walk back.
+/// Targets or reports too large for that memory give `ExpenseError::TooLarge`.
+pub(super) fn subset_sum<T: Amount>(
+    entries: &[T],
+    amount: T,
+    options: SubsetOptions,
+) -> Result<Combination<T>, ExpenseError> {
+    let target: usize = amount.try_into().map_err(|_| ExpenseError::NoMatch)?;
+    if target >= SUBSET_MAX_SUMS {
+        return Err(ExpenseError::TooLarge);
+    }
+    let values: Vec<(T, usize)> = entries
+        .iter()
+        .cloned()
+        .zip(0..)
+        .filter(|(v, _)| *v > T::zero() && (*v).try_into().is_ok_and(|v: usize| v <= target))
+        .collect();
+    let sizes: Vec<usize> = values
+        .iter()
+        .filter_map(|(v, _)| (*v).try_into().ok())
+        .collect();
+    if options.smallest && values.len() * (target / 64 + 1) > SUBSET_MAX_WORDS {
+        return Err(ExpenseError::TooLarge);
+    }
+    let max_size = options.max_size.unwrap_or(usize::MAX);
+    let mut best = vec![usize::MAX; target + 1];
+    let mut first: Vec<Option<usize>> = vec![None; target + 1];
+    let mut taken = if options.smallest {
+        vec![vec![0_u64; target / 64 + 1]; values.len()]
+    } else {
+        Vec::new()
+    };
+    best[0] = 0;
+    for (i, value) in sizes.iter().cloned().enumerate() {
+        if !options.smallest && first[target].is_some() {
+            break;
+        }
+        for sum in (value..=target).rev() {
+            let previous = best[sum - value];
+            if previous >= max_size {
+                continue;
+            }
+            if first[sum].is_none() && sum > 0 {
+                first[sum] = Some(i);
+            }
+            if previous + 1 < best[sum] {
+                best[sum] = previous + 1;
+                if options.smallest {
+                    taken[i][sum / 64] |= 1 << (sum % 64);
+                }
             }
         }
     }
-    0
+    if best[target] == usize::MAX {
+        return Err(ExpenseError::NoMatch);
+    }
+    if options.smallest {
+        let chosen = smallest_subset(&sizes, &taken, target);
+        return Ok(Combination::new(&values, &chosen));
+    }
+    let mut chosen = Vec::new();
+    let mut sum = target;
+    while let Some(i) = first[sum] {
+        chosen.push(i);
+        sum -= sizes[i];
+    }
+    if chosen.len() > max_size {
+        let smallest = SubsetOptions {
+            smallest: true,
+            ..options
+        };
+        return subset_sum(entries, amount, smallest);
+    }
+    Ok(Combination::new(&values, &chosen))
+}
+
+pub(super) fn parse_expenses<T: Amount>(list: Vec<String>) -> Result<Vec<T>, ExpenseError> {
+    list.into_iter()
+        .map(|s| s.trim().parse::<T>().map_err(|_| ExpenseError::Parse(s)))
+        .collect()
+}
+
+/// Fixed-point amount with up to two decimals, such as `12.34`, as a whole number of cents.
+pub(super) fn parse_cents<T: Amount>(inp: &str) -> Result<T, ExpenseError> {
+    let error = || ExpenseError::Parse(inp.to_string());
+    let trimmed = inp.trim();
+    let (negative, unsigned) = match trimmed.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, trimmed),
+    };
+    let mut split = unsigned.splitn(2, '.');
+    let units = split.next().unwrap_or("");
+    let decimals = split.next().unwrap_or("");
+    if units.is_empty() || decimals.len() > 2 || unsigned.ends_with('.') {
+        return Err(error());
+    }
+    let digits = format!("{}{:0<2}", units, decimals);
+    if !digits.chars().all(|c| c.is_ascii_digit()) {
+        return Err(error());
+    }
+    let cents = digits.parse::<i128>().map_err(|_| error())?;
+    T::try_from(if negative { -cents } else { cents }).map_err(|_| error())
+}
+
+pub(super) fn parse_cent_expenses<T: Amount>(list: Vec<String>) -> Result<Vec<T>, ExpenseError> {
+    list.iter().map(|s| parse_cents(s)).collect()
+}
+
+pub(super) fn expenses<T: Amount>(input: Vec<T>) -> Result<T, ExpenseError> {
+    find_k_sum(&input, 2, T::from(2020))
+        .ok_or(ExpenseError::NoMatch)?
+        .product()
+}
+
+pub(super) fn expenses2<T: Amount>(input: Vec<T>) -> Result<T, ExpenseError> {
+    find_k_sum(&input, 3, T::from(2020))
+        .ok_or(ExpenseError::NoMatch)?
+        .product()
 }
 
 #[test]
 fn test_expenses() {
     let input = vec![1721, 979, 366, 299, 675, 1456];
-    assert_eq!(514579, expenses(input))
+    assert_eq!(Ok(514579), expenses(input))
 }
 
 #[test]
 fn test_expenses2() {
     let input = vec![1721, 979, 366, 299, 675, 1456];
-    assert_eq!(241861950, expenses2(input))
+    assert_eq!(Ok(241861950), expenses2(input))
+}
+
+#[test]
+fn test_expenses_overflow() {
+    let input = vec![3000, 3000, -3980, 7];
+    assert_eq!(Err(ExpenseError::Overflow), expenses2(input));
+    let input = vec![3000_i64, 3000, -3980, 7];
+    assert_eq!(Ok(-35820000000), expenses2(input));
+    let input = vec![u64::MAX - 2, 1010, 1010];
+    assert_eq!(Ok(1020100), expenses(input));
+    let input = vec![i128::MAX, i128::MIN + 2021, 2, 3];
+    assert_eq!(Err(ExpenseError::Overflow), expenses(input));
+    assert_eq!(Err(ExpenseError::NoMatch), expenses(vec![1_i128, 2, 3]));
+}
+
+#[test]
+fn test_k_sums_out_of_range() {
+    let input = vec![i32::MAX, i32::MAX, i32::MIN, -1, 1];
+    let pairs: Vec<Vec<usize>> = k_sums(&input, 2, -1, Selection::Distinct)
+        .map(|c| c.indices)
+        .collect();
+    assert_eq!(vec![vec![0, 2], vec![1, 2]], pairs);
+    assert_eq!(1, k_sums(&input, 3, i32::MIN, Selection::Distinct).count());
+    assert_eq!(2, k_sums(&input, 3, i32::MAX, Selection::Distinct).count());
+    let input = vec![u64::MAX, u64::MAX - 1, 1];
+    assert_eq!(1, k_sums(&input, 2, u64::MAX, Selection::Distinct).count());
+}
+
+#[test]
+fn test_find_k_sum() {
+    let input = vec![1721, 979, 366, 299, 675, 1456];
+    let pair = find_k_sum(&input, 2, 2020).unwrap();
+    assert_eq!(vec![0, 3], pair.indices);
+    assert_eq!(vec![1721, 299], pair.entries);
+    let triple = find_k_sum(&input, 3, 2020).unwrap();
+    assert_eq!(vec![1, 2, 4], triple.indices);
+    assert_eq!(vec![979, 366, 675], triple.entries);
+    let quadruple = find_k_sum(&input, 4, 3365).unwrap();
+    assert_eq!(vec![0, 1, 2, 3], quadruple.indices);
+    assert_eq!(vec![366], find_k_sum(&input, 1, 366).unwrap().entries);
+    assert_eq!(None, find_k_sum(&input, 5, 2020));
+    assert_eq!(None, find_k_sum(&input, 7, 5496));
+}
+
+#[test]
+fn test_find_k_sum_distinct_entries() {
+    // A single 1010 can not pair with itself
+    assert_eq!(Err(ExpenseError::NoMatch), expenses(vec![1010, 500]));
+    assert_eq!(None, find_k_sum(&[1010], 2, 2020));
+    let pair = find_k_sum(&[3, 1010, 7, 1010], 2, 2020).unwrap();
+    assert_eq!(vec![1, 3], pair.indices);
+    assert_eq!(Err(ExpenseError::NoMatch), expenses2(vec![1000, 20, 10]));
+    assert_eq!(None, find_k_sum(&[673, 674], 3, 2020));
+    let triple = find_k_sum(&[673, 674, 673], 3, 2020).unwrap();
+    assert_eq!(vec![0, 1, 2], triple.indices);
+    assert_eq!(None, find_k_sum(&[673, 673, 673], 3, 2020));
+}
+
+#[test]
+fn test_find_k_sum_with_replacement() {
+    let pair = find_k_sum_with(&[500, 1010], 2, 2020, Selection::WithReplacement).unwrap();
+    assert_eq!(vec![1, 1], pair.indices);
+    assert_eq!(vec![1010, 1010], pair.entries);
+    let triple = find_k_sum_with(&[1000, 20, 10], 3, 2020, Selection::WithReplacement).unwrap();
+    assert_eq!(vec![0, 0, 1], triple.indices);
+    let triple = find_k_sum_with(&[673, 674], 3, 2020, Selection::WithReplacement).unwrap();
+    assert_eq!(vec![673, 673, 674], triple.entries);
+    assert_eq!(
+        None,
+        find_k_sum_with(&[3, 5], 2, 7, Selection::WithReplacement)
+    );
+    assert_eq!(
+        Some(vec![5, 5, 5]),
+        find_k_sum_with(&[5], 3, 15, Selection::WithReplacement).map(|c| c.entries)
+    );
+}
+
+#[test]
+fn test_find_k_sum_large() {
+    let input: Vec<i32> = (0..2000).map(|i| i * 7 % 2003).collect();
+    let target = input[10] + input[500] + input[1000] + input[1500] + input[1999];
+    let combination = find_k_sum(&input, 5, target).unwrap();
+    assert_eq!(5, combination.indices.len());
+    assert_eq!(target, combination.entries.iter().sum::<i32>());
+}
+
+#[test]
+fn test_k_sums() {
+    let input = vec![1721, 979, 366, 299, 675, 1456, 1654, 1041];
+    let pairs: Vec<Vec<usize>> = k_sums(&input, 2, 2020, Selection::Distinct)
+        .map(|c| c.indices)
+        .collect();
+    assert_eq!(vec![vec![0, 3], vec![2, 6], vec![1, 7]], pairs);
+    let triples: Vec<Vec<i32>> = k_sums(&input, 3, 2020, Selection::Distinct)
+        .map(|c| c.entries)
+        .collect();
+    assert_eq!(vec![vec![979, 366, 675]], triples);
+    assert_eq!(0, k_sums(&input, 2, 1, Selection::Distinct).count());
+    assert_eq!(None, k_sums(&input, 9, 2020, Selection::Distinct).next());
+}
+
+#[test]
+fn test_k_sums_duplicates() {
+    let input = vec![1010, 1010, 1010, 3, 2017, 2017];
+    let pairs: Vec<Vec<usize>> = k_sums(&input, 2, 2020, Selection::Distinct)
+        .map(|c| c.indices)
+        .collect();
+    assert_eq!(
+        vec![vec![3, 4], vec![3, 5], vec![0, 1], vec![0, 2], vec![1, 2]],
+        pairs
+    );
+    assert_eq!(5, k_sums(&input, 2, 2020, Selection::Distinct).count());
+    assert_eq!(
+        8,
+        k_sums(&input, 2, 2020, Selection::WithReplacement).count()
+    );
+    assert_eq!(
+        8,
+        k_sums(&input, 2, 2020, Selection::WithReplacement)
+            .collect::<Vec<Combination<i32>>>()
+            .len()
+    );
+    assert_eq!(3, k_sums(&input, 1, 1010, Selection::Distinct).count());
+    assert_eq!(1, k_sums(&input, 0, 0, Selection::Distinct).count());
+}
+
+#[test]
+fn test_k_sums_count() {
+    let input: Vec<i32> = (1..=60).collect();
+    for k in 1..=4 {
+        for selection in [Selection::Distinct, Selection::WithReplacement] {
+            let listed = k_sums(&input, k, 90, selection).collect::<Vec<Combination<i32>>>();
+            assert!(listed.iter().all(|c| c.entries.iter().sum::<i32>() == 90));
+            assert_eq!(listed.len(), k_sums(&input, k, 90, selection).count());
+        }
+    }
+    // Partitions of 10 into three different parts: 1+2+7, 1+3+6, 1+4+5, 2+3+5
+    assert_eq!(4, k_sums(&input, 3, 10, Selection::Distinct).count());
+    // Plus 1+1+8, 2+2+6, 3+3+4, 2+4+4
+    assert_eq!(8, k_sums(&input, 3, 10, Selection::WithReplacement).count());
+}
+
+#[test]
+fn test_nearest_k_sum() {
+    let input = vec![1721, 979, 366, 299, 675, 1456];
+    let exact = nearest_k_sum(&input, 2, 2020, Tie::Under).unwrap();
+    assert_eq!(vec![1721, 299], exact.entries);
+    // 366 + 1456 = 1822 and 979 + 1456 = 2435 miss, 1721 + 366 = 2087 is 67 over
+    let nearest = nearest_k_sum(&input, 2, 2030, Tie::Under).unwrap();
+    assert_eq!(vec![1721, 299], nearest.entries);
+    let nearest = nearest_k_sum(&input, 3, 2000, Tie::Under).unwrap();
+    assert_eq!(vec![979, 366, 675], nearest.entries);
+    assert_eq!(None, nearest_k_sum(&input, 7, 2020, Tie::Under));
+    assert_eq!(
+        Some(vec![]),
+        nearest_k_sum(&input, 0, 5, Tie::Under).map(|c| c.entries)
+    );
+}
+
+#[test]
+fn test_nearest_k_sum_tie() {
+    let input = vec![10, 20, 30];
+    // 10 + 20 = 30 and 10 + 30 = 40 are both 5 away from 35
+    let under = nearest_k_sum(&input, 2, 35, Tie::Under).unwrap();
+    assert_eq!(vec![10, 20], under.entries);
+    let over = nearest_k_sum(&input, 2, 35, Tie::Over).unwrap();
+    assert_eq!(vec![10, 30], over.entries);
+    let over = nearest_k_sum(&[5_u64, 7], 1, 6, Tie::Over).unwrap();
+    assert_eq!(vec![7], over.entries);
+}
+
+#[test]
+fn test_nearest_k_sums() {
+    let input = vec![1721, 979, 366, 299, 675, 1456];
+    let sums: Vec<i32> = nearest_k_sums(&input, 2, 2020, Tie::Over, 4)
+        .iter()
+        .map(|c| c.entries.iter().sum())
+        .collect();
+    assert_eq!(vec![2020, 2087, 2131, 1822], sums);
+    let all = nearest_k_sums(&input, 2, 2020, Tie::Under, 100);
+    assert_eq!(15, all.len());
+    assert!(nearest_k_sums(&input, 2, 2020, Tie::Under, 0).is_empty());
+    let sums: Vec<i32> = nearest_k_sums(&input, 3, 2020, Tie::Under, 3)
+        .iter()
+        .map(|c| c.entries.iter().sum())
+        .collect();
+    assert_eq!(vec![2020, 1953, 2121], sums);
+}
+
+#[test]
+fn test_nearest_k_sums_large() {
+    let input: Vec<i64> = (0..100_000).map(|i| i * 7919 % 1_000_003 * 2).collect();
+    let nearest = nearest_k_sums(&input, 2, 1_000_001, Tie::Under, 5);
+    assert_eq!(5, nearest.len());
+    let distances: Vec<i64> = nearest
+        .iter()
+        .map(|c| (c.entries.iter().sum::<i64>() - 1_000_001).abs())
+        .collect();
+    assert!(distances.windows(2).all(|w| w[0] <= w[1]));
+    assert_eq!(1, distances[0]);
+}
+
+#[test]
+fn test_parse_expenses() {
+    let list = vec!["1721".to_string(), "979".to_string(), "366".to_string()];
+    assert_eq!(Ok(vec![1721, 979, 366]), parse_expenses::<i32>(list));
+    let list = vec!["1721".to_string(), "-979".to_string()];
+    assert_eq!(
+        Err(ExpenseError::Parse("-979".to_string())),
+        parse_expenses::<u64>(list)
+    );
+}
+
+#[test]
+fn test_parse_cents() {
+    assert_eq!(Ok(1234), parse_cents::<i64>("12.34"));
+    assert_eq!(Ok(1230), parse_cents::<i64>("12.3"));
+    assert_eq!(Ok(1200), parse_cents::<u64>("12"));
+    assert_eq!(Ok(-5), parse_cents::<i32>("-0.05"));
+    assert_eq!(Ok(5), parse_cents::<i32>(" 0.05 "));
+    for inp in &["12.345", "12.", ".5", "1.-5", "1,50", "", "-"] {
+        assert_eq!(
+            Err(ExpenseError::Parse(inp.to_string())),
+            parse_cents::<i64>(inp)
+        );
+    }
+    assert!(parse_cents::<u64>("-1.00").is_err());
+    assert!(parse_cents::<i32>("21474836.48").is_err());
+    assert_eq!(Ok(2147483647), parse_cents::<i32>("21474836.47"));
+    let list = vec!["17.21".to_string(), "2.99".to_string(), "8.00".to_string()];
+    let input = parse_cent_expenses::<i64>(list).unwrap();
+    assert_eq!(
+        vec![1721, 299],
+        find_k_sum(&input, 2, 2020).unwrap().entries
+    );
+}
+
+#[test]
+fn test_subset_sum() {
+    let input = vec![1721, 979, 366, 299, 675, 1456];
+    let smallest = SubsetOptions {
+        max_size: None,
+        smallest: true,
+    };
+    let subset = subset_sum(&input, 2020, smallest).unwrap();
+    assert_eq!(vec![1721, 299], subset.entries);
+    let subset = subset_sum(&input, 2020, SubsetOptions::default()).unwrap();
+    assert_eq!(2020, subset.entries.iter().sum::<i32>());
+    let subset = subset_sum(&input, 3775, smallest).unwrap();
+    assert_eq!(vec![1, 2, 3, 4, 5], subset.indices);
+    let capped = SubsetOptions {
+        max_size: Some(4),
+        smallest: false,
+    };
+    assert_eq!(Err(ExpenseError::NoMatch), subset_sum(&input, 3775, capped));
+    assert_eq!(
+        Err(ExpenseError::NoMatch),
+        subset_sum(&input, 2, SubsetOptions::default())
+    );
+    assert_eq!(
+        Err(ExpenseError::NoMatch),
+        subset_sum(&input, -5, SubsetOptions::default())
+    );
+    let empty = subset_sum(&input, 0, SubsetOptions::default()).unwrap();
+    assert!(empty.indices.is_empty());
+}
+
+#[test]
+fn test_subset_sum_size_cap() {
+    let input = vec![1, 1, 1, 1, 4, -3, 0];
+    let first = subset_sum(&input, 4, SubsetOptions::default()).unwrap();
+    assert_eq!(vec![0, 1, 2, 3], first.indices);
+    let capped = SubsetOptions {
+        max_size: Some(2),
+        smallest: false,
+    };
+    assert_eq!(vec![4], subset_sum(&input, 4, capped).unwrap().indices);
+    let capped = SubsetOptions {
+        max_size: Some(0),
+        smallest: false,
+    };
+    assert_eq!(Err(ExpenseError::NoMatch), subset_sum(&input, 4, capped));
+    // Ten thousand amounts in cents against a $10k target, the first entry already hits it
+    let mut input: Vec<i64> = (1..10_000).collect();
+    input.insert(0, 1_000_000);
+    let first = subset_sum(&input, 1_000_000, SubsetOptions::default()).unwrap();
+    assert_eq!(vec![0], first.indices);
+    // The smallest subset would keep 1.25 GB of bits for the same report
+    let smallest = SubsetOptions {
+        max_size: None,
+        smallest: true,
+    };
+    assert_eq!(
+        Err(ExpenseError::TooLarge),
+        subset_sum(&input, 1_000_000, smallest)
+    );
+    assert_eq!(
+        Err(ExpenseError::TooLarge),
+        subset_sum(&input, 1_i64 << 40, SubsetOptions::default())
+    );
 }
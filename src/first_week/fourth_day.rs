@@ -181,13 +181,13 @@ fn test_create_passports() {
     ];
     let passports = create_passports(input);
     assert_eq!(4, passports.len());
-    assert_eq!(true, passports[0].ecl.is_some());
+    assert!(passports[0].ecl.is_some());
     assert_eq!("gry".to_string(), passports[0].ecl.clone().unwrap());
-    assert_eq!(true, passports[1].cid.is_some());
+    assert!(passports[1].cid.is_some());
     assert_eq!("350".to_string(), passports[1].cid.clone().unwrap());
-    assert_eq!(true, passports[2].iyr.is_some());
-    assert_eq!(2013, passports[2].iyr.clone().unwrap());
-    assert_eq!(true, passports[3].hcl.is_some());
+    assert!(passports[2].iyr.is_some());
+    assert_eq!(2013, passports[2].iyr.unwrap());
+    assert!(passports[3].hcl.is_some());
     assert_eq!("#cfa07d".to_string(), passports[3].hcl.clone().unwrap());
 }
 
@@ -1,57 +1,776 @@
 use regex::Regex;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::io::{self, BufRead};
+use std::sync::{mpsc, Mutex};
+use std::thread;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// What counts as one character of a password.
+#[derive(Debug, Clone, Copy, Ord, PartialOrd, Eq, PartialEq)]
+pub(super) enum Segmentation {
+    // Unicode scalar values, as `str::chars` gives them.
+    Chars,
+    // Extended grapheme clusters, so `é` with a combining accent or `👍🏽` are one character.
+    Graphemes,
+}
+
+impl Segmentation {
+    fn split<'a>(&self, inp: &'a str) -> Vec<&'a str> {
+        match self {
+            Segmentation::Chars => inp
+                .char_indices()
+                .map(|(i, c)| &inp[i..i + c.len_utf8()])
+                .collect(),
+            Segmentation::Graphemes => inp.graphemes(true).collect(),
+        }
+    }
+}
 
 #[derive(Debug, Ord, PartialOrd, Eq, PartialEq)]
-struct Policy {
+pub(super) struct Policy {
     first_number: usize,
     second_number: usize,
-    character: char,
+    character: String,
     password: String,
+    segmentation: Segmentation,
 }
 
-fn create_policies(list: Vec<String>) -> Vec<Policy> {
-    let r = Regex::new(r"[\s.?!,-/:]+").unwrap();
-    list.into_iter()
-        .map(|s| {
-            let parts: Vec<&str> = r.split(s.as_ref()).collect();
-            Policy {
-                first_number: parts.get(0).map(|s| s.parse::<usize>().unwrap()).unwrap(),
-                second_number: parts.get(1).map(|s| s.parse::<usize>().unwrap()).unwrap(),
-                character: parts.get(2).map(|s| s.chars().next().unwrap()).unwrap(),
-                password: parts.get(3).map(|s| s.to_string()).unwrap(),
-            }
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub(super) enum PolicyErrorKind {
+    ExpectedNumber,
+    NumberTooLarge,
+    Expected(char),
+    MissingCharacter,
+    ZeroPosition,
+    MinAboveMax,
+}
+
+/// Where a policy line went wrong, line and column are 1-based and count chars.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub(super) struct PolicyError {
+    pub line: usize,
+    pub column: usize,
+    pub kind: PolicyErrorKind,
+}
+
+// Reads `<min>-<max> <char>: <password>`, the rest of the line is kept as the password.
+struct PolicyParser<'a> {
+    rest: &'a str,
+    segmentation: Segmentation,
+    line: usize,
+    column: usize,
+}
+
+impl<'a> PolicyParser<'a> {
+    fn new(inp: &'a str, line: usize, segmentation: Segmentation) -> Self {
+        Self {
+            rest: inp,
+            segmentation,
+            line,
+            column: 1,
+        }
+    }
+
+    fn error(&self, column: usize, kind: PolicyErrorKind) -> PolicyError {
+        PolicyError {
+            line: self.line,
+            column,
+            kind,
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest.chars().next()
+    }
+
+    // Consumes `len` bytes of the line.
+    fn advance(&mut self, len: usize) -> &'a str {
+        let (taken, rest) = self.rest.split_at(len);
+        self.column += taken.chars().count();
+        self.rest = rest;
+        taken
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), PolicyError> {
+        if self.peek() == Some(expected) {
+            self.advance(expected.len_utf8());
+            Ok(())
+        } else {
+            Err(self.error(self.column, PolicyErrorKind::Expected(expected)))
+        }
+    }
+
+    // Number and the column it starts at.
+    fn number(&mut self) -> Result<(usize, usize), PolicyError> {
+        let column = self.column;
+        let mut number = None;
+        while let Some(d) = self.peek().and_then(|c| c.to_digit(10)) {
+            let next = number
+                .unwrap_or(0_usize)
+                .checked_mul(10)
+                .and_then(|n| n.checked_add(d as usize))
+                .ok_or_else(|| self.error(column, PolicyErrorKind::NumberTooLarge))?;
+            number = Some(next);
+            self.advance(1);
+        }
+        number
+            .map(|n| (column, n))
+            .ok_or_else(|| self.error(column, PolicyErrorKind::ExpectedNumber))
+    }
+
+    fn policy(mut self) -> Result<Policy, PolicyError> {
+        let (first_column, first_number) = self.number()?;
+        self.expect('-')?;
+        let (second_column, second_number) = self.number()?;
+        self.expect(' ')?;
+        let character = match self.segmentation.split(self.rest).first() {
+            Some(c) => self.advance(c.len()).to_string(),
+            None => return Err(self.error(self.column, PolicyErrorKind::MissingCharacter)),
+        };
+        self.expect(':')?;
+        self.expect(' ')?;
+        if first_number == 0 {
+            return Err(self.error(first_column, PolicyErrorKind::ZeroPosition));
+        }
+        if first_number > second_number {
+            return Err(self.error(second_column, PolicyErrorKind::MinAboveMax));
+        }
+        Ok(Policy {
+            first_number,
+            second_number,
+            character,
+            password: self.rest.to_string(),
+            segmentation: self.segmentation,
         })
+    }
+}
+
+fn create_policies(
+    list: &[String],
+    segmentation: Segmentation,
+) -> Result<Vec<Policy>, PolicyError> {
+    list.iter()
+        .enumerate()
+        .map(|(i, s)| PolicyParser::new(s, i + 1, segmentation).policy())
         .collect()
 }
 
-pub(super) fn incorrect_passwords(list: Vec<String>) -> u32 {
-    create_policies(list).into_iter().fold(0, |acc, policy| {
-        let times = policy.password.matches(policy.character).count();
-        if times >= policy.first_number && times <= policy.second_number {
-            return acc + 1;
+impl Policy {
+    // The password split into characters, as the policy counts them.
+    fn characters(&self) -> Vec<&str> {
+        self.segmentation.split(&self.password)
+    }
+
+    fn occurrences(&self) -> usize {
+        self.characters()
+            .into_iter()
+            .filter(|c| *c == self.character)
+            .count()
+    }
+
+    // Whether each of the two 1-based positions holds the policy character.
+    fn holds(&self) -> (bool, bool) {
+        let characters = self.characters();
+        let at = |position: usize| characters.get(position - 1) == Some(&self.character.as_str());
+        (at(self.first_number), at(self.second_number))
+    }
+
+    fn explain_positions(&self) -> String {
+        let (first, second) = (self.first_number, self.second_number);
+        match self.holds() {
+            (true, true) => format!("positions {} and {} both match", first, second),
+            (true, false) => format!("only position {} matches", first),
+            (false, true) => format!("only position {} matches", second),
+            (false, false) => format!("neither position {} nor {} matches", first, second),
+        }
+    }
+}
+
+/// Check a password has to pass, given the policy it was listed with.
+pub(super) trait PasswordRule {
+    fn check(&self, policy: &Policy) -> bool;
+
+    /// Why the password passes or fails, in words.
+    fn explain(&self, policy: &Policy) -> String;
+}
+
+/// The character shows up between the two numbers of times, both included.
+pub(super) struct CountRange;
+
+impl PasswordRule for CountRange {
+    fn check(&self, policy: &Policy) -> bool {
+        let times = policy.occurrences();
+        times >= policy.first_number && times <= policy.second_number
+    }
+
+    fn explain(&self, policy: &Policy) -> String {
+        let times = policy.occurrences();
+        format!(
+            "'{}' occurs {} time{}, allowed {}-{}",
+            policy.character,
+            times,
+            if times == 1 { "" } else { "s" },
+            policy.first_number,
+            policy.second_number
+        )
+    }
+}
+
+/// The character is at exactly one of the two positions.
+pub(super) struct ExactlyOnePosition;
+
+impl PasswordRule for ExactlyOnePosition {
+    fn check(&self, policy: &Policy) -> bool {
+        let (first, second) = policy.holds();
+        first ^ second
+    }
+
+    fn explain(&self, policy: &Policy) -> String {
+        policy.explain_positions()
+    }
+}
+
+/// The character is at both positions.
+pub(super) struct AllPositions;
+
+impl PasswordRule for AllPositions {
+    fn check(&self, policy: &Policy) -> bool {
+        let (first, second) = policy.holds();
+        first && second
+    }
+
+    fn explain(&self, policy: &Policy) -> String {
+        policy.explain_positions()
+    }
+}
+
+/// None of the characters show up in the password, even as part of a grapheme.
+pub(super) struct Forbidden(pub Vec<char>);
+
+impl PasswordRule for Forbidden {
+    fn check(&self, policy: &Policy) -> bool {
+        !policy.password.chars().any(|c| self.0.contains(&c))
+    }
+
+    fn explain(&self, policy: &Policy) -> String {
+        match policy.password.chars().find(|c| self.0.contains(c)) {
+            Some(c) => format!("contains forbidden '{}'", c),
+            None => "no forbidden characters".to_string(),
+        }
+    }
+}
+
+/// The password matches the regular expression.
+pub(super) struct RegexMatch(pub Regex);
+
+impl PasswordRule for RegexMatch {
+    fn check(&self, policy: &Policy) -> bool {
+        self.0.is_match(&policy.password)
+    }
+
+    fn explain(&self, policy: &Policy) -> String {
+        if self.check(policy) {
+            format!("matches /{}/", self.0)
+        } else {
+            format!("does not match /{}/", self.0)
         }
-        acc
-    })
+    }
 }
 
-pub(super) fn correct_passwords(list: Vec<String>) -> u32 {
-    create_policies(list).into_iter().fold(0, |acc, policy| {
-        let first_ch = policy
-            .password
-            .chars()
-            .nth(policy.first_number - 1)
-            .map(|c| c == policy.character)
-            .unwrap_or(false);
-        let second_ch = policy
-            .password
-            .chars()
-            .nth(policy.second_number - 1)
-            .map(|c| c == policy.character)
-            .unwrap_or(false);
-        if first_ch ^ second_ch {
-            return acc + 1;
-        }
-        acc
+/// The password has at least this many different characters.
+pub(super) struct MinDistinct(pub usize);
+
+impl PasswordRule for MinDistinct {
+    fn check(&self, policy: &Policy) -> bool {
+        policy
+            .characters()
+            .into_iter()
+            .collect::<HashSet<&str>>()
+            .len()
+            >= self.0
+    }
+
+    fn explain(&self, policy: &Policy) -> String {
+        let distinct = policy
+            .characters()
+            .into_iter()
+            .collect::<HashSet<&str>>()
+            .len();
+        format!(
+            "{} distinct characters, at least {} needed",
+            distinct, self.0
+        )
+    }
+}
+
+/// How the results of the rules in a set add up.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub(super) enum Combine {
+    All,
+    Any,
+    // An odd number of rules pass.
+    Xor,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(super) enum ConfigErrorKind {
+    UnknownRule(String),
+    UnknownCombine(String),
+    MissingArgument,
+    InvalidNumber(String),
+    InvalidRegex(String),
+}
+
+/// Where a rule set config went wrong, the line is 1-based.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(super) struct ConfigError {
+    pub line: usize,
+    pub kind: ConfigErrorKind,
+}
+
+/// Rules chosen by a config, passing as a whole depending on how they combine.
+pub(super) struct RuleSet {
+    pub combine: Combine,
+    pub rules: Vec<Box<dyn PasswordRule>>,
+}
+
+impl RuleSet {
+    /// Reads one rule or `combine <all|any|xor>` per line, `#` starts a comment.
+    ///
+    /// Rules are `count-range`, `exactly-one-position`, `all-positions`,
+    /// `forbidden <chars>`, `regex <pattern>` and `min-distinct <n>`. Rules combine
+    /// with `all` unless told otherwise.
+    pub fn from_config(lines: &[String]) -> Result<Self, ConfigError> {
+        let mut set = Self {
+            combine: Combine::All,
+            rules: Vec::new(),
+        };
+        for (i, line) in lines.iter().enumerate() {
+            let error = |kind| ConfigError { line: i + 1, kind };
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            let mut split = line.splitn(2, char::is_whitespace);
+            let name = split.next().unwrap_or("");
+            let argument = split.next().map(|a| a.trim()).filter(|a| !a.is_empty());
+            let required = || argument.ok_or_else(|| error(ConfigErrorKind::MissingArgument));
+            let rule: Box<dyn PasswordRule> = match name {
+                "combine" => {
+                    set.combine = match required()? {
+                        "all" => Combine::All,
+                        "any" => Combine::Any,
+                        "xor" => Combine::Xor,
+                        other => {
+                            return Err(error(ConfigErrorKind::UnknownCombine(other.to_string())))
+                        }
+                    };
+                    continue;
+                }
+                "count-range" => Box::new(CountRange),
+                "exactly-one-position" => Box::new(ExactlyOnePosition),
+                "all-positions" => Box::new(AllPositions),
+                "forbidden" => Box::new(Forbidden(required()?.chars().collect())),
+                "regex" => {
+                    let pattern = required()?;
+                    match Regex::new(pattern) {
+                        Ok(r) => Box::new(RegexMatch(r)),
+                        Err(_) => {
+                            return Err(error(ConfigErrorKind::InvalidRegex(pattern.to_string())))
+                        }
+                    }
+                }
+                "min-distinct" => {
+                    let number = required()?;
+                    match number.parse() {
+                        Ok(n) => Box::new(MinDistinct(n)),
+                        Err(_) => {
+                            return Err(error(ConfigErrorKind::InvalidNumber(number.to_string())))
+                        }
+                    }
+                }
+                other => return Err(error(ConfigErrorKind::UnknownRule(other.to_string()))),
+            };
+            set.rules.push(rule);
+        }
+        Ok(set)
+    }
+}
+
+impl PasswordRule for RuleSet {
+    fn check(&self, policy: &Policy) -> bool {
+        let mut results = self.rules.iter().map(|r| r.check(policy));
+        match self.combine {
+            Combine::All => results.all(|r| r),
+            Combine::Any => results.any(|r| r),
+            Combine::Xor => results.filter(|r| *r).count() % 2 == 1,
+        }
+    }
+
+    fn explain(&self, policy: &Policy) -> String {
+        self.rules
+            .iter()
+            .map(|r| r.explain(policy))
+            .collect::<Vec<String>>()
+            .join("; ")
+    }
+}
+
+/// Password edited to pass a rule, with the number of single character edits it took.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(super) struct Repair {
+    pub edits: usize,
+    pub password: String,
+}
+
+/// Rule that can tell the fewest insertions, deletions or substitutions to pass it.
+pub(super) trait RepairableRule: PasswordRule {
+    /// `None` if no edit of the password can pass the rule.
+    fn repair(&self, policy: &Policy) -> Option<Repair>;
+}
+
+// Character written where the policy character has to go away.
+fn filler(character: &str) -> &'static str {
+    if character == "?" {
+        "!"
+    } else {
+        "?"
+    }
+}
+
+impl RepairableRule for CountRange {
+    // Every edit moves the count by one at most, so the gap to the range is the answer.
+    // Short counts turn other characters into the policy one before inserting more.
+    fn repair(&self, policy: &Policy) -> Option<Repair> {
+        let mut characters = policy.characters();
+        let times = policy.occurrences();
+        let edits = if times < policy.first_number {
+            let missing = policy.first_number - times;
+            let mut left = missing;
+            for c in characters.iter_mut().filter(|c| **c != policy.character) {
+                if left == 0 {
+                    break;
+                }
+                *c = &policy.character;
+                left -= 1;
+            }
+            characters.extend((0..left).map(|_| policy.character.as_str()));
+            missing
+        } else if times > policy.second_number {
+            let extra = times - policy.second_number;
+            let mut left = extra;
+            characters.retain(|c| {
+                let drop = left > 0 && *c == policy.character;
+                left -= drop as usize;
+                !drop
+            });
+            extra
+        } else {
+            0
+        };
+        Some(Repair {
+            edits,
+            password: characters.concat(),
+        })
+    }
+}
+
+impl RepairableRule for ExactlyOnePosition {
+    // One edit fixes either position, unless the password is too short to reach the
+    // first one and has to grow up to it.
+    fn repair(&self, policy: &Policy) -> Option<Repair> {
+        // Both positions are the same one, it can not hold and not hold the character.
+        if policy.first_number == policy.second_number {
+            return None;
+        }
+        let mut characters = policy.characters();
+        let (first, second) = (policy.first_number, policy.second_number);
+        let edits = match policy.holds() {
+            (true, false) | (false, true) => 0,
+            (true, true) => {
+                characters[second - 1] = filler(&policy.character);
+                1
+            }
+            (false, false) if characters.len() >= first => {
+                characters[first - 1] = &policy.character;
+                1
+            }
+            (false, false) => {
+                let missing = first - characters.len();
+                characters.extend((1..missing).map(|_| filler(&policy.character)));
+                characters.push(&policy.character);
+                missing
+            }
+        };
+        Some(Repair {
+            edits,
+            password: characters.concat(),
+        })
+    }
+}
+
+/// Line number and the suggested repair of every line failing `rule`.
+pub(super) fn repairs(
+    list: &[String],
+    rule: &dyn RepairableRule,
+    segmentation: Segmentation,
+) -> Result<Vec<(usize, Option<Repair>)>, PolicyError> {
+    Ok(create_policies(list, segmentation)?
+        .iter()
+        .enumerate()
+        .filter(|(_, policy)| !rule.check(policy))
+        .map(|(i, policy)| (i + 1, rule.repair(policy)))
+        .collect())
+}
+
+/// Number of passwords in the list passing `rule`.
+pub(super) fn valid_passwords(
+    list: &[String],
+    rule: &dyn PasswordRule,
+    segmentation: Segmentation,
+) -> Result<u32, PolicyError> {
+    Ok(create_policies(list, segmentation)?
+        .iter()
+        .filter(|policy| rule.check(policy))
+        .count() as u32)
+}
+
+/// Lines read by `scan_passwords` and how many pass each policy.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub(super) struct ScanCounts {
+    pub lines: usize,
+    pub count_valid: usize,
+    pub position_valid: usize,
+}
+
+impl ScanCounts {
+    fn tally(&mut self, policy: &Policy) {
+        self.lines += 1;
+        self.count_valid += CountRange.check(policy) as usize;
+        self.position_valid += ExactlyOnePosition.check(policy) as usize;
+    }
+
+    fn merge(&mut self, other: ScanCounts) {
+        self.lines += other.lines;
+        self.count_valid += other.count_valid;
+        self.position_valid += other.position_valid;
+    }
+}
+
+#[derive(Debug)]
+pub(super) enum ScanError {
+    Io(io::Error),
+    Policy(PolicyError),
+}
+
+// Lines handed to a scanning thread at once.
+const SCAN_CHUNK: usize = 4096;
+
+// Drops the `\n` or `\r\n` kept by `read_line`, any other `\r` belongs to the password.
+fn trim_line_end(line: &str) -> &str {
+    match line.strip_suffix('\n') {
+        Some(line) => line.strip_suffix('\r').unwrap_or(line),
+        None => line,
+    }
+}
+
+// Next lines of the reader, with no more than `size` of them.
+fn read_chunk<R: BufRead>(reader: &mut R, size: usize) -> io::Result<Vec<String>> {
+    let mut chunk = Vec::with_capacity(size);
+    let mut line = String::new();
+    while chunk.len() < size && reader.read_line(&mut line)? > 0 {
+        chunk.push(trim_line_end(&line).to_string());
+        line.clear();
+    }
+    Ok(chunk)
+}
+
+fn scan_chunk(
+    first_line: usize,
+    chunk: &[String],
+    segmentation: Segmentation,
+) -> Result<ScanCounts, PolicyError> {
+    let mut counts = ScanCounts::default();
+    for (i, line) in chunk.iter().enumerate() {
+        counts.tally(&PolicyParser::new(line, first_line + i, segmentation).policy()?);
+    }
+    Ok(counts)
+}
+
+/// Checks both policies on every line of `reader` in a single pass.
+///
+/// With more than one thread, chunks of lines are handed to the threads through a
+/// bounded channel, so memory stays flat however long the list is. Errors point to the
+/// first bad line either way.
+pub(super) fn scan_passwords<R: BufRead>(
+    mut reader: R,
+    segmentation: Segmentation,
+    threads: usize,
+) -> Result<ScanCounts, ScanError> {
+    let mut counts = ScanCounts::default();
+    if threads <= 1 {
+        let mut line = String::new();
+        while reader.read_line(&mut line).map_err(ScanError::Io)? > 0 {
+            let policy = PolicyParser::new(trim_line_end(&line), counts.lines + 1, segmentation)
+                .policy()
+                .map_err(ScanError::Policy)?;
+            counts.tally(&policy);
+            line.clear();
+        }
+        return Ok(counts);
+    }
+    let (sender, receiver) = mpsc::sync_channel::<(usize, Vec<String>)>(threads);
+    let receiver = Mutex::new(receiver);
+    let results = thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut result: Result<ScanCounts, PolicyError> = Ok(ScanCounts::default());
+                    loop {
+                        let next = receiver.lock().expect("Scanning thread panicked").recv();
+                        let (first_line, chunk) = match next {
+                            Ok(next) => next,
+                            Err(_) => return result,
+                        };
+                        // Chunks still have to be taken so the reader is not blocked.
+                        if let Ok(counts) = &mut result {
+                            match scan_chunk(first_line, &chunk, segmentation) {
+                                Ok(chunk_counts) => counts.merge(chunk_counts),
+                                Err(e) => result = Err(e),
+                            }
+                        }
+                    }
+                })
+            })
+            .collect();
+        let mut first_line = 1;
+        let read = loop {
+            match read_chunk(&mut reader, SCAN_CHUNK) {
+                Ok(chunk) if chunk.is_empty() => break Ok(()),
+                Ok(chunk) => {
+                    let len = chunk.len();
+                    sender
+                        .send((first_line, chunk))
+                        .expect("Scanning threads stopped");
+                    first_line += len;
+                }
+                Err(e) => break Err(e),
+            }
+        };
+        drop(sender);
+        let results: Vec<Result<ScanCounts, PolicyError>> = workers
+            .into_iter()
+            .map(|w| w.join().expect("Scanning thread panicked"))
+            .collect();
+        read.map(|_| results)
     })
+    .map_err(ScanError::Io)?;
+    let mut first_error: Option<PolicyError> = None;
+    for result in results {
+        match result {
+            Ok(worker_counts) => counts.merge(worker_counts),
+            Err(e) if first_error.is_none_or(|f| e.line < f.line) => first_error = Some(e),
+            Err(_) => {}
+        }
+    }
+    match first_error {
+        Some(e) => Err(ScanError::Policy(e)),
+        None => Ok(counts),
+    }
+}
+
+/// Verdict of both policies on one line of the list.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize)]
+pub(super) struct AuditLine {
+    pub line: usize,
+    pub first_number: usize,
+    pub second_number: usize,
+    pub character: String,
+    pub password: String,
+    pub count_valid: bool,
+    pub count_reason: String,
+    pub position_valid: bool,
+    pub position_reason: String,
+}
+
+/// Lines passing only one of the two policies.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Serialize)]
+pub(super) struct Disagreements {
+    pub count_only: Vec<usize>,
+    pub position_only: Vec<usize>,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub(super) enum AuditFormat {
+    Csv,
+    Json,
+}
+
+/// Every line of the list checked against the count and the position policies.
+#[derive(Debug, Serialize)]
+pub(super) struct Audit {
+    pub lines: Vec<AuditLine>,
+    pub disagreements: Disagreements,
+}
+
+impl Audit {
+    pub fn new(list: &[String], segmentation: Segmentation) -> Result<Self, PolicyError> {
+        let lines: Vec<AuditLine> = create_policies(list, segmentation)?
+            .into_iter()
+            .enumerate()
+            .map(|(i, policy)| AuditLine {
+                line: i + 1,
+                first_number: policy.first_number,
+                second_number: policy.second_number,
+                character: policy.character.clone(),
+                count_valid: CountRange.check(&policy),
+                count_reason: CountRange.explain(&policy),
+                position_valid: ExactlyOnePosition.check(&policy),
+                position_reason: ExactlyOnePosition.explain(&policy),
+                password: policy.password,
+            })
+            .collect();
+        let mut disagreements = Disagreements::default();
+        for line in &lines {
+            match (line.count_valid, line.position_valid) {
+                (true, false) => disagreements.count_only.push(line.line),
+                (false, true) => disagreements.position_only.push(line.line),
+                _ => {}
+            }
+        }
+        Ok(Self {
+            lines,
+            disagreements,
+        })
+    }
+
+    pub fn summary(&self) -> String {
+        let count_only = &self.disagreements.count_only;
+        let position_only = &self.disagreements.position_only;
+        format!(
+            "{} of {} lines disagree: {} pass only the count policy, {} pass only the position policy",
+            count_only.len() + position_only.len(),
+            self.lines.len(),
+            count_only.len(),
+            position_only.len()
+        )
+    }
+
+    /// One row per line for CSV, the whole audit with the disagreements for JSON.
+    pub fn export(&self, format: AuditFormat) -> String {
+        match format {
+            AuditFormat::Csv => {
+                let mut writer = csv::Writer::from_writer(Vec::new());
+                for line in &self.lines {
+                    writer.serialize(line).expect("Could not write audit line");
+                }
+                String::from_utf8(writer.into_inner().expect("Could not write audit"))
+                    .expect("Audit is not valid UTF-8")
+            }
+            AuditFormat::Json => serde_json::to_string_pretty(self).expect("Could not write audit"),
+        }
+    }
 }
 
 #[test]
@@ -65,42 +784,626 @@ fn test_create_policies() {
         Policy {
             first_number: 5,
             second_number: 11,
-            character: 't',
+            character: "t".to_string(),
             password: "glhbttzvzttkdx".to_string(),
+            segmentation: Segmentation::Chars,
         },
         Policy {
             first_number: 2,
             second_number: 4,
-            character: 'f',
+            character: "f".to_string(),
             password: "cfkmf".to_string(),
+            segmentation: Segmentation::Chars,
         },
         Policy {
             first_number: 9,
             second_number: 12,
-            character: 'm',
+            character: "m".to_string(),
             password: "mmmmmmmmmmmmm".to_string(),
+            segmentation: Segmentation::Chars,
         },
     ];
-    assert_eq!(expected, create_policies(input))
+    assert_eq!(Ok(expected), create_policies(&input, Segmentation::Chars))
 }
 
 #[test]
-fn test_incorrect_passwords() {
+fn test_create_policies_verbatim_password() {
     let input = vec![
-        "5-11 t: glhbttzvzttkdx".to_string(),
-        "2-4 f: cfkmf".to_string(),
-        "9-12 m: mmmmmmmmmmmmm".to_string(),
+        "1-3 a: a-b.c:d!e,f".to_string(),
+        "2-2 :: x: y ".to_string(),
+        "1-1 -: ".to_string(),
     ];
-    assert_eq!(1, incorrect_passwords(input))
+    let policies = create_policies(&input, Segmentation::Chars).unwrap();
+    assert_eq!("a-b.c:d!e,f", policies[0].password);
+    assert_eq!(":", policies[1].character);
+    assert_eq!("x: y ", policies[1].password);
+    assert_eq!("-", policies[2].character);
+    assert_eq!("", policies[2].password);
+}
+
+#[test]
+fn test_create_policies_errors() {
+    let cases = vec![
+        ("0-3 a: abc", 1, PolicyErrorKind::ZeroPosition),
+        ("4-3 a: abc", 3, PolicyErrorKind::MinAboveMax),
+        ("x-3 a: abc", 1, PolicyErrorKind::ExpectedNumber),
+        ("1-x a: abc", 3, PolicyErrorKind::ExpectedNumber),
+        ("1 3 a: abc", 2, PolicyErrorKind::Expected('-')),
+        ("1-3  a: abc", 6, PolicyErrorKind::Expected(':')),
+        ("1-3 a abc", 6, PolicyErrorKind::Expected(':')),
+        ("1-3 a:abc", 7, PolicyErrorKind::Expected(' ')),
+        ("1-3 ", 5, PolicyErrorKind::MissingCharacter),
+        ("", 1, PolicyErrorKind::ExpectedNumber),
+        (
+            "1-99999999999999999999 a: abc",
+            3,
+            PolicyErrorKind::NumberTooLarge,
+        ),
+    ];
+    for (inp, column, kind) in cases {
+        let input = vec!["1-3 a: abcde".to_string(), inp.to_string()];
+        assert_eq!(
+            Err(PolicyError {
+                line: 2,
+                column,
+                kind
+            }),
+            create_policies(&input, Segmentation::Chars)
+        );
+    }
+}
+
+#[test]
+fn test_incorrect_passwords() {
+    let input = [
+        "5-11 t: glhbttzvzttkdx",
+        "2-4 f: cfkmf",
+        "9-12 m: mmmmmmmmmmmmm",
+    ]
+    .join("\n");
+    let counts = scan_passwords(input.as_bytes(), Segmentation::Chars, 1);
+    assert_eq!(1, counts.unwrap().count_valid)
 }
 
 #[test]
 fn test_correct_passwords() {
+    let input = [
+        "1-3 a: abcde",
+        "1-3 a: cbade",
+        "1-3 b: cdefg",
+        "2-9 c: ccccccccc",
+    ]
+    .join("\n");
+    let counts = scan_passwords(input.as_bytes(), Segmentation::Chars, 1);
+    assert_eq!(2, counts.unwrap().position_valid)
+}
+
+#[test]
+fn test_password_rules() {
+    let policies = create_policies(
+        &[
+            "1-3 a: abcde".to_string(),
+            "1-3 b: cdefg".to_string(),
+            "2-9 c: ccccccccc".to_string(),
+            "1-5 a: abcda".to_string(),
+        ],
+        Segmentation::Chars,
+    )
+    .unwrap();
+    let results =
+        |rule: &dyn PasswordRule| -> Vec<bool> { policies.iter().map(|p| rule.check(p)).collect() };
+    assert_eq!(vec![true, false, true, true], results(&CountRange));
+    assert_eq!(
+        vec![true, false, false, false],
+        results(&ExactlyOnePosition)
+    );
+    assert_eq!(vec![false, false, true, true], results(&AllPositions));
+    assert_eq!(
+        vec![false, true, true, false],
+        results(&Forbidden(vec!['a', 'z']))
+    );
+    let regex = RegexMatch(Regex::new("^[a-e]+$").unwrap());
+    assert_eq!(vec![true, false, true, true], results(&regex));
+    assert_eq!(vec![true, true, false, true], results(&MinDistinct(4)));
+    assert_eq!(vec![true, true, false, false], results(&MinDistinct(5)));
+}
+
+#[test]
+fn test_rule_set() {
+    let policies = create_policies(
+        &[
+            "1-3 a: abcde".to_string(),
+            "1-3 b: cdefg".to_string(),
+            "2-9 c: ccccccccc".to_string(),
+        ],
+        Segmentation::Chars,
+    )
+    .unwrap();
+    let config = |lines: &[&str]| {
+        let lines: Vec<String> = lines.iter().map(|s| s.to_string()).collect();
+        RuleSet::from_config(&lines).unwrap()
+    };
+    let results =
+        |rule: &RuleSet| -> Vec<bool> { policies.iter().map(|p| rule.check(p)).collect() };
+    let rules = config(&["count-range", "exactly-one-position"]);
+    assert_eq!(Combine::All, rules.combine);
+    assert_eq!(vec![true, false, false], results(&rules));
+    let rules = config(&[
+        "combine any",
+        "",
+        "count-range  # sled policy",
+        "exactly-one-position",
+    ]);
+    assert_eq!(vec![true, false, true], results(&rules));
+    let rules = config(&["combine xor", "count-range", "exactly-one-position"]);
+    assert_eq!(vec![false, false, true], results(&rules));
+    let rules = config(&[
+        "combine xor",
+        "count-range",
+        "exactly-one-position",
+        "forbidden z",
+    ]);
+    assert_eq!(vec![true, true, false], results(&rules));
+    let rules = config(&["regex ^c", "min-distinct 2"]);
+    assert_eq!(vec![false, true, false], results(&rules));
+    assert!(config(&[]).check(&policies[1]));
+}
+
+#[test]
+fn test_rule_set_errors() {
+    let cases = vec![
+        (
+            "shortest 3",
+            ConfigErrorKind::UnknownRule("shortest".to_string()),
+        ),
+        (
+            "combine none",
+            ConfigErrorKind::UnknownCombine("none".to_string()),
+        ),
+        ("combine", ConfigErrorKind::MissingArgument),
+        ("forbidden  ", ConfigErrorKind::MissingArgument),
+        (
+            "min-distinct x",
+            ConfigErrorKind::InvalidNumber("x".to_string()),
+        ),
+        (
+            "regex [a-",
+            ConfigErrorKind::InvalidRegex("[a-".to_string()),
+        ),
+    ];
+    for (line, kind) in cases {
+        let lines = vec!["# rules".to_string(), line.to_string()];
+        assert_eq!(
+            Some(ConfigError { line: 2, kind }),
+            RuleSet::from_config(&lines).err()
+        );
+    }
+}
+
+#[test]
+fn test_valid_passwords() {
+    let input = vec![
+        "1-3 a: abcde".to_string(),
+        "1-3 b: cdefg".to_string(),
+        "2-9 c: ccccccccc".to_string(),
+    ];
+    let rules = RuleSet {
+        combine: Combine::Any,
+        rules: vec![Box::new(CountRange), Box::new(ExactlyOnePosition)],
+    };
+    assert_eq!(Ok(2), valid_passwords(&input, &rules, Segmentation::Chars));
+    assert_eq!(
+        Ok(1),
+        valid_passwords(&input, &AllPositions, Segmentation::Chars)
+    );
+}
+
+#[test]
+fn test_explain() {
+    let policies = create_policies(
+        &[
+            "5-11 t: glhbttzvzttkdx".to_string(),
+            "2-4 f: cfkff".to_string(),
+            "1-3 a: abcde".to_string(),
+            "1-3 b: cdbfg".to_string(),
+            "1-3 a: xyz".to_string(),
+        ],
+        Segmentation::Chars,
+    )
+    .unwrap();
+    assert_eq!(
+        "'t' occurs 4 times, allowed 5-11",
+        CountRange.explain(&policies[0])
+    );
+    assert_eq!(
+        "'a' occurs 1 time, allowed 1-3",
+        CountRange.explain(&policies[2])
+    );
+    assert_eq!(
+        "positions 2 and 4 both match",
+        ExactlyOnePosition.explain(&policies[1])
+    );
+    assert_eq!(
+        "only position 1 matches",
+        ExactlyOnePosition.explain(&policies[2])
+    );
+    assert_eq!(
+        "only position 3 matches",
+        AllPositions.explain(&policies[3])
+    );
+    assert_eq!(
+        "neither position 1 nor 3 matches",
+        ExactlyOnePosition.explain(&policies[4])
+    );
+    assert_eq!(
+        "contains forbidden 'y'",
+        Forbidden(vec!['y', 'z']).explain(&policies[4])
+    );
+    assert_eq!(
+        "no forbidden characters",
+        Forbidden(vec!['q']).explain(&policies[4])
+    );
+    let regex = RegexMatch(Regex::new("^x").unwrap());
+    assert_eq!("matches /^x/", regex.explain(&policies[4]));
+    assert_eq!("does not match /^x/", regex.explain(&policies[2]));
+    let rules = RuleSet {
+        combine: Combine::All,
+        rules: vec![Box::new(CountRange), Box::new(MinDistinct(4))],
+    };
+    assert_eq!(
+        "'a' occurs 0 times, allowed 1-3; 3 distinct characters, at least 4 needed",
+        rules.explain(&policies[4])
+    );
+}
+
+#[test]
+fn test_audit() {
     let input = vec![
         "1-3 a: abcde".to_string(),
-        "1-3 a: cbade".to_string(),
         "1-3 b: cdefg".to_string(),
         "2-9 c: ccccccccc".to_string(),
+        "1-3 a: bab".to_string(),
     ];
-    assert_eq!(2, correct_passwords(input))
+    let audit = Audit::new(&input, Segmentation::Chars).unwrap();
+    assert_eq!(4, audit.lines.len());
+    assert_eq!(
+        AuditLine {
+            line: 3,
+            first_number: 2,
+            second_number: 9,
+            character: "c".to_string(),
+            password: "ccccccccc".to_string(),
+            count_valid: true,
+            count_reason: "'c' occurs 9 times, allowed 2-9".to_string(),
+            position_valid: false,
+            position_reason: "positions 2 and 9 both match".to_string(),
+        },
+        audit.lines[2]
+    );
+    assert_eq!(
+        Disagreements {
+            count_only: vec![3, 4],
+            position_only: vec![],
+        },
+        audit.disagreements
+    );
+    assert_eq!(
+        "2 of 4 lines disagree: 2 pass only the count policy, 0 pass only the position policy",
+        audit.summary()
+    );
+    let input = vec!["1-3 a: abcde".to_string(), "1-x a: abcde".to_string()];
+    assert_eq!(
+        2,
+        Audit::new(&input, Segmentation::Chars).err().unwrap().line
+    );
+}
+
+#[test]
+fn test_audit_export() {
+    let input = vec!["1-3 a: abcde".to_string(), "2-9 c: cc,\"c\"".to_string()];
+    let audit = Audit::new(&input, Segmentation::Chars).unwrap();
+    let csv = audit.export(AuditFormat::Csv);
+    let rows: Vec<&str> = csv.lines().collect();
+    assert_eq!(
+        "line,first_number,second_number,character,password,count_valid,count_reason,\
+         position_valid,position_reason",
+        rows[0]
+    );
+    assert_eq!(
+        "1,1,3,a,abcde,true,\"'a' occurs 1 time, allowed 1-3\",true,only position 1 matches",
+        rows[1]
+    );
+    assert_eq!(
+        "2,2,9,c,\"cc,\"\"c\"\"\",true,\"'c' occurs 3 times, allowed 2-9\",true,\
+         only position 2 matches",
+        rows[2]
+    );
+    let json: serde_json::Value = serde_json::from_str(&audit.export(AuditFormat::Json)).unwrap();
+    assert_eq!("cc,\"c\"", json["lines"][1]["password"]);
+    assert_eq!(true, json["lines"][1]["position_valid"]);
+    assert_eq!(
+        "only position 2 matches",
+        json["lines"][1]["position_reason"]
+    );
+    assert!(json["disagreements"]["count_only"]
+        .as_array()
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn test_segmentation() {
+    assert_eq!(
+        vec!["e", "\u{301}", "👍", "🏽"],
+        Segmentation::Chars.split("e\u{301}👍🏽")
+    );
+    assert_eq!(
+        vec!["e\u{301}", "👍🏽"],
+        Segmentation::Graphemes.split("e\u{301}👍🏽")
+    );
+    assert!(Segmentation::Graphemes.split("").is_empty());
+}
+
+#[test]
+fn test_create_policies_graphemes() {
+    let family = "👨\u{200d}👩\u{200d}👧";
+    let input = vec![
+        "1-2 e\u{301}: e\u{301}e\u{301}x".to_string(),
+        format!("1-3 {}: {}👨x", family, family),
+    ];
+    let policies = create_policies(&input, Segmentation::Graphemes).unwrap();
+    assert_eq!("e\u{301}", policies[0].character);
+    assert_eq!("e\u{301}e\u{301}x", policies[0].password);
+    assert_eq!(family, policies[1].character);
+    assert_eq!(
+        Err(PolicyError {
+            line: 1,
+            column: 6,
+            kind: PolicyErrorKind::Expected(':')
+        }),
+        create_policies(&input, Segmentation::Chars)
+    );
+    let input = vec!["1-3 é:x".to_string()];
+    assert_eq!(
+        Err(PolicyError {
+            line: 1,
+            column: 7,
+            kind: PolicyErrorKind::Expected(' ')
+        }),
+        create_policies(&input, Segmentation::Graphemes)
+    );
+}
+
+#[test]
+fn test_password_rules_graphemes() {
+    let family = "👨\u{200d}👩\u{200d}👧";
+    let input = vec![
+        "1-2 e\u{301}: e\u{301}e\u{301}x".to_string(),
+        format!("1-3 {}: {}👨x", family, family),
+        format!("1-1 👨: {}", family),
+        "2-2 🏽: 👍🏽👍🏽".to_string(),
+    ];
+    let graphemes = create_policies(&input, Segmentation::Graphemes).unwrap();
+    let results = |rule: &dyn PasswordRule| -> Vec<bool> {
+        graphemes.iter().map(|p| rule.check(p)).collect()
+    };
+    assert_eq!(vec![true, true, false, false], results(&CountRange));
+    assert_eq!(
+        vec![false, true, false, false],
+        results(&ExactlyOnePosition)
+    );
+    assert_eq!(vec![true, false, false, false], results(&AllPositions));
+    assert_eq!(vec![true, true, false, false], results(&MinDistinct(2)));
+    assert_eq!(
+        "'e\u{301}' occurs 2 times, allowed 1-2",
+        CountRange.explain(&graphemes[0])
+    );
+    assert_eq!(
+        "only position 1 matches",
+        ExactlyOnePosition.explain(&graphemes[1])
+    );
+    let chars = create_policies(&input[2..], Segmentation::Chars).unwrap();
+    assert!(CountRange.check(&chars[0]));
+    assert!(CountRange.check(&chars[1]));
+    assert!(AllPositions.check(&chars[1]));
+    assert!(!MinDistinct(3).check(&chars[1]));
+    assert!(!Forbidden(vec!['🏽']).check(&graphemes[3]));
+    assert_eq!(
+        Ok(1),
+        valid_passwords(&input, &ExactlyOnePosition, Segmentation::Graphemes)
+    );
+}
+
+#[test]
+fn test_scan_passwords() {
+    let input = "1-3 a: abcde\n1-3 b: cdefg\r\n2-9 c: ccccccccc\n";
+    for threads in 1..=3 {
+        assert_eq!(
+            ScanCounts {
+                lines: 3,
+                count_valid: 2,
+                position_valid: 1,
+            },
+            scan_passwords(input.as_bytes(), Segmentation::Chars, threads).unwrap()
+        );
+    }
+    let counts = scan_passwords("".as_bytes(), Segmentation::Chars, 2).unwrap();
+    assert_eq!(ScanCounts::default(), counts);
+}
+
+#[test]
+fn test_scan_passwords_large() {
+    let list: Vec<String> = (0..10_000)
+        .map(|i| {
+            format!(
+                "{}-{} {}: {}",
+                i % 5 + 1,
+                i % 7 + 5,
+                "abc".chars().nth(i % 3).unwrap(),
+                "abcab".repeat(i % 4 + 1)
+            )
+        })
+        .collect();
+    let expected = ScanCounts {
+        lines: list.len(),
+        count_valid: valid_passwords(&list, &CountRange, Segmentation::Chars).unwrap() as usize,
+        position_valid: valid_passwords(&list, &ExactlyOnePosition, Segmentation::Chars).unwrap()
+            as usize,
+    };
+    let input = list.join("\n");
+    for threads in &[1, 4] {
+        let counts = scan_passwords(input.as_bytes(), Segmentation::Chars, *threads).unwrap();
+        assert_eq!(expected, counts);
+    }
+}
+
+#[test]
+fn test_scan_passwords_errors() {
+    let mut list: Vec<String> = (0..9000).map(|_| "1-3 a: abcde".to_string()).collect();
+    list[8999] = "3-1 a: abcde".to_string();
+    list[5000] = "1-3 a abcde".to_string();
+    let input = list.join("\n");
+    for threads in &[1, 3] {
+        match scan_passwords(input.as_bytes(), Segmentation::Chars, *threads) {
+            Err(ScanError::Policy(e)) => assert_eq!(
+                PolicyError {
+                    line: 5001,
+                    column: 6,
+                    kind: PolicyErrorKind::Expected(':')
+                },
+                e
+            ),
+            other => panic!("Unexpected scan result {:?}", other),
+        }
+    }
+    let input: &[u8] = b"1-3 a: abcde\n1-3 a: \xff\n";
+    for threads in &[1, 2] {
+        assert!(matches!(
+            scan_passwords(input, Segmentation::Chars, *threads),
+            Err(ScanError::Io(_))
+        ));
+    }
+}
+
+#[cfg(test)]
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + (ca != *cb) as usize;
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(row[j + 1] + 1);
+        }
+    }
+    row[b.len()]
+}
+
+#[test]
+fn test_repair() {
+    let input = vec![
+        "1-3 a: abcde".to_string(),
+        "3-4 b: cdbfg".to_string(),
+        "5-6 b: bb".to_string(),
+        "2-9 c: ccccccccccccc".to_string(),
+        "1-3 a: aba".to_string(),
+        "2-3 d: xy".to_string(),
+        "4-7 d: xy".to_string(),
+        "2-2 a: aa".to_string(),
+    ];
+    let policies = create_policies(&input, Segmentation::Chars).unwrap();
+    let repair = |rule: &dyn RepairableRule, i: usize| {
+        rule.repair(&policies[i]).map(|r| (r.edits, r.password))
+    };
+    assert_eq!(Some((0, "abcde".to_string())), repair(&CountRange, 0));
+    assert_eq!(Some((2, "bbbfg".to_string())), repair(&CountRange, 1));
+    assert_eq!(Some((3, "bbbbb".to_string())), repair(&CountRange, 2));
+    assert_eq!(Some((4, "ccccccccc".to_string())), repair(&CountRange, 3));
+    assert_eq!(
+        Some((0, "abcde".to_string())),
+        repair(&ExactlyOnePosition, 0)
+    );
+    assert_eq!(Some((1, "ab?".to_string())), repair(&ExactlyOnePosition, 4));
+    assert_eq!(Some((1, "xd".to_string())), repair(&ExactlyOnePosition, 5));
+    assert_eq!(
+        Some((2, "xy?d".to_string())),
+        repair(&ExactlyOnePosition, 6)
+    );
+    assert_eq!(None, repair(&ExactlyOnePosition, 7));
+    for policy in &policies {
+        let rules: [&dyn RepairableRule; 2] = [&CountRange, &ExactlyOnePosition];
+        for rule in rules {
+            if let Some(repair) = rule.repair(policy) {
+                let repaired = Policy {
+                    password: repair.password.clone(),
+                    character: policy.character.clone(),
+                    ..*policy
+                };
+                assert!(rule.check(&repaired));
+                assert_eq!(
+                    repair.edits,
+                    edit_distance(&policy.password, &repair.password)
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_repair_graphemes() {
+    let input = vec!["2-3 e\u{301}: e\u{301}xe".to_string()];
+    let policies = create_policies(&input, Segmentation::Graphemes).unwrap();
+    let repair = CountRange.repair(&policies[0]).unwrap();
+    assert_eq!(1, repair.edits);
+    assert_eq!("e\u{301}e\u{301}e", repair.password);
+    let repair = ExactlyOnePosition.repair(&policies[0]).unwrap();
+    assert_eq!("e\u{301}e\u{301}e", repair.password);
+    assert_eq!(1, repair.edits);
+    let repairs = repairs(&input, &CountRange, Segmentation::Graphemes).unwrap();
+    assert_eq!(1, repairs.len());
+    assert_eq!(1, repairs[0].0);
+}
+
+#[test]
+fn test_repairs() {
+    let input = vec![
+        "1-3 a: abcde".to_string(),
+        "1-3 b: cdefg".to_string(),
+        "2-9 c: ccccccccc".to_string(),
+    ];
+    let found = repairs(&input, &CountRange, Segmentation::Chars).unwrap();
+    assert_eq!(
+        vec![(
+            2,
+            Some(Repair {
+                edits: 1,
+                password: "bdefg".to_string()
+            })
+        )],
+        found
+    );
+    let found = repairs(&input, &ExactlyOnePosition, Segmentation::Chars).unwrap();
+    assert_eq!(
+        vec![2, 3],
+        found.iter().map(|(l, _)| *l).collect::<Vec<usize>>()
+    );
+    assert_eq!(
+        Some("cccccccc?".to_string()),
+        found[1].1.as_ref().map(|r| r.password.clone())
+    );
+}
+
+#[test]
+fn test_trim_line_end() {
+    assert_eq!("1-3 a: ab", trim_line_end("1-3 a: ab\n"));
+    assert_eq!("1-3 a: ab", trim_line_end("1-3 a: ab\r\n"));
+    assert_eq!("1-3 a: ab\r", trim_line_end("1-3 a: ab\r\r\n"));
+    assert_eq!("1-3 a: ab\r", trim_line_end("1-3 a: ab\r"));
+    assert_eq!("1-3 a: ab\n", trim_line_end("1-3 a: ab\n\n"));
+    let input = "1-3 \r: a\r\r\n1-3 \r: \r\r";
+    let counts = scan_passwords(input.as_bytes(), Segmentation::Chars, 1).unwrap();
+    assert_eq!(2, counts.count_valid);
+    assert_eq!(1, counts.position_valid);
 }
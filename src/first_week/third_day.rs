@@ -1,46 +1,391 @@
-pub(super) fn tree_map(input: Vec<String>, line_step: usize, position_step: usize) -> usize {
-    let map: Vec<Vec<char>> = input
-        .into_iter()
-        .map(|s| s.chars().collect::<Vec<char>>())
-        .collect();
-    let mut index = 0;
-    let line_size = map.get(0).unwrap().len();
-
-    (line_step..map.len())
-        .step_by(line_step)
-        .fold(0, |acc, il| {
-            index += position_step;
-            if index > line_size - 1 {
-                index -= line_size;
+use std::collections::HashSet;
+use std::convert::TryFrom;
+use std::fmt;
+use std::num::TryFromIntError;
+use std::ops::RangeInclusive;
+
+use crate::number_theory::gcd;
+
+/// Move of the toboggan, `right` columns and `down` rows at a time. Either can be
+/// negative, left and up, and `Slope::rational` takes fractions of a cell.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub(super) struct Slope {
+    pub right: i64,
+    pub down: i64,
+}
+
+impl Slope {
+    pub fn new(right: i64, down: i64) -> Self {
+        Self { right, down }
+    }
+
+    /// Slope of `right.0 / right.1` columns and `down.0 / down.1` rows per move.
+    ///
+    /// The path only lands on whole cells every few moves, so those moves are merged
+    /// into one. `None` if a denominator is zero or the merged move does not fit.
+    pub fn rational(right: (i64, i64), down: (i64, i64)) -> Option<Self> {
+        if right.1 == 0 || down.1 == 0 {
+            return None;
+        }
+        // Halves fit in 64 bits and whole products in 128, so only the end can overflow.
+        let reduce = |(n, d): (i64, i64)| {
+            let g = gcd(n.unsigned_abs(), d.unsigned_abs()) as i128;
+            (n as i128 / g * d.signum() as i128, (d as i128).abs() / g)
+        };
+        let (right, down) = (reduce(right), reduce(down));
+        let moves = right.1 / gcd(right.1 as u64, down.1 as u64) as i128 * down.1;
+        Some(Self::new(
+            i64::try_from(right.0 * (moves / right.1)).ok()?,
+            i64::try_from(down.0 * (moves / down.1)).ok()?,
+        ))
+    }
+}
+
+// Attempts of `check_slots` come as `(down, right)`.
+impl TryFrom<(usize, usize)> for Slope {
+    type Error = TryFromIntError;
+
+    fn try_from(attempt: (usize, usize)) -> Result<Self, Self::Error> {
+        Ok(Self::new(
+            i64::try_from(attempt.1)?,
+            i64::try_from(attempt.0)?,
+        ))
+    }
+}
+
+/// Cells the toboggan landed on as `(row, column)`, and how many had a tree.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub(super) struct Ride {
+    pub trees: usize,
+    pub visited: Vec<(usize, usize)>,
+}
+
+// Cells the path lands on as `(row, column)`, columns not wrapped yet.
+//
+// Rows may differ in width, so columns are only wrapped once their row is known. They
+// are kept in 128 bits instead: fewer than 2^63 rows of steps below 2^64 cannot overflow.
+fn path(map: &[Vec<char>], slope: Slope, start: (usize, usize)) -> Vec<(usize, i128)> {
+    let mut path = Vec::new();
+    if slope == Slope::new(0, 0) {
+        return path;
+    }
+    let mut row = match i64::try_from(start.0) {
+        Ok(row) => row,
+        Err(_) => return path,
+    };
+    let mut column = start.1 as i128;
+    loop {
+        row = match row.checked_add(slope.down) {
+            Some(row) => row,
+            None => break,
+        };
+        column += slope.right as i128;
+        let line = match usize::try_from(row).ok().and_then(|r| map.get(r)) {
+            Some(line) if !line.is_empty() => line,
+            _ => break,
+        };
+        let width = line.len() as i128;
+        if slope.down == 0 && column.rem_euclid(width) == start.1 as i128 % width {
+            break;
+        }
+        path.push((row as usize, column));
+    }
+    path
+}
+
+fn create_map(input: &[String]) -> Vec<Vec<char>> {
+    input.iter().map(|s| s.chars().collect()).collect()
+}
+
+/// Follows `slope` from `start` until the path leaves the top or the bottom of the map.
+///
+/// Columns wrap around, as the map repeats to both sides. A path staying on one row
+/// stops once it is back at the starting column.
+pub(super) fn ride(input: &[String], slope: Slope, start: (usize, usize)) -> Ride {
+    let map = create_map(input);
+    let mut ride = Ride::default();
+    for (row, column) in path(&map, slope, start) {
+        let line = &map[row];
+        let column = column.rem_euclid(line.len() as i128) as usize;
+        ride.visited.push((row, column));
+        if line[column] == '#' {
+            ride.trees += 1;
+        }
+    }
+    ride
+}
+
+// Width of one copy of the map, rows shorter than the widest still wrap on their own.
+fn copy_width(map: &[Vec<char>]) -> i128 {
+    map.iter().map(|l| l.len()).max().unwrap_or(0).max(1) as i128
+}
+
+// Most columns a rendering spans, steep sideways slopes would need billions of them.
+const RENDER_MAX_COLUMNS: i128 = 100_000;
+
+// Copies of the map, counted from the original one, needed to show the paths unwrapped.
+// `None` when they would span more than `RENDER_MAX_COLUMNS`.
+fn repeats(
+    map: &[Vec<char>],
+    paths: &[Vec<(usize, i128)>],
+    start: (usize, usize),
+) -> Option<(i128, i128)> {
+    let width = copy_width(map);
+    let (low, high) = paths
+        .iter()
+        .flatten()
+        .map(|(_, column)| *column)
+        .chain(Some(start.1 as i128))
+        .fold((0, 0), |(low, high), column| {
+            let copy = column.div_euclid(width);
+            (low.min(copy), high.max(copy))
+        });
+    if (high - low + 1) * width > RENDER_MAX_COLUMNS {
+        return None;
+    }
+    Some((low, high))
+}
+
+/// The map repeated to the sides as far as the path goes, with `O` on the open
+/// squares the toboggan lands on and `X` on the trees it hits.
+///
+/// Every row repeats on its own width, as in `ride`, and blank rows stay blank. `None`
+/// when the path goes too far sideways to draw.
+pub(super) fn render_path(input: &[String], slope: Slope, start: (usize, usize)) -> Option<String> {
+    let map = create_map(input);
+    let path = path(&map, slope, start);
+    let (low, high) = repeats(&map, std::slice::from_ref(&path), start)?;
+    let landed: HashSet<(usize, i128)> = path.into_iter().collect();
+    let width = copy_width(&map);
+    let mut rendered = String::new();
+    for (row, line) in map.iter().enumerate() {
+        if line.is_empty() {
+            rendered.push('\n');
+            continue;
+        }
+        for column in low * width..(high + 1) * width {
+            let c = line[column.rem_euclid(line.len() as i128) as usize];
+            rendered.push(match c {
+                '#' if landed.contains(&(row, column)) => 'X',
+                _ if landed.contains(&(row, column)) => 'O',
+                _ => c,
+            });
+        }
+        rendered.push('\n');
+    }
+    Some(rendered)
+}
+
+const PATH_COLOURS: [&str; 8] = [
+    "#e6194b", "#3cb44b", "#4363d8", "#f58231", "#911eb4", "#42d4f4", "#f032e6", "#9a6324",
+];
+
+/// SVG of the repeated map with the path of every slope from the top left corner,
+/// each in its own colour. Trees hit are outlined in the colour of the path. `None`
+/// when the paths go too far sideways to draw.
+pub(super) fn render_svg(input: &[String], slopes: &[Slope]) -> Option<String> {
+    const CELL: i128 = 10;
+    let map = create_map(input);
+    let paths: Vec<Vec<(usize, i128)>> = slopes.iter().map(|s| path(&map, *s, (0, 0))).collect();
+    let (low, high) = repeats(&map, &paths, (0, 0))?;
+    let width = copy_width(&map);
+    let columns = (high - low + 1) * width;
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\">\n",
+        columns * CELL,
+        map.len() as i128 * CELL
+    );
+    svg.push_str("<rect width=\"100%\" height=\"100%\" fill=\"white\"/>\n");
+    let x = |column: i128| (column - low * width) * CELL;
+    for (row, line) in map.iter().enumerate() {
+        if line.is_empty() {
+            continue;
+        }
+        for column in low * width..(high + 1) * width {
+            if line[column.rem_euclid(line.len() as i128) as usize] == '#' {
+                svg.push_str(&format!(
+                    "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"#2f4f2f\"/>\n",
+                    x(column),
+                    row as i128 * CELL,
+                    CELL,
+                    CELL
+                ));
+            }
+        }
+    }
+    for (i, path) in paths.iter().enumerate() {
+        let colour = PATH_COLOURS[i % PATH_COLOURS.len()];
+        let points: Vec<String> = Some((0, 0))
+            .iter()
+            .chain(path)
+            .map(|(row, column)| {
+                format!(
+                    "{},{}",
+                    x(*column) + CELL / 2,
+                    *row as i128 * CELL + CELL / 2
+                )
+            })
+            .collect();
+        svg.push_str(&format!(
+            "<polyline points=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"2\"/>\n",
+            points.join(" "),
+            colour
+        ));
+        for (row, column) in path {
+            let line = &map[*row];
+            if line[column.rem_euclid(line.len() as i128) as usize] == '#' {
+                svg.push_str(&format!(
+                    "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"2\"/>\n",
+                    x(*column),
+                    *row as i128 * CELL,
+                    CELL,
+                    CELL,
+                    colour
+                ));
             }
-            if let Some(line) = map.get(il) {
-                if let Some(position) = line.get(index) {
-                    if *position == '#' {
-                        return acc + 1;
+        }
+    }
+    svg.push_str("</svg>\n");
+    Some(svg)
+}
+
+// Trees of one row of the map, column `c` is bit `c % 64` of word `c / 64`.
+struct TreeRow {
+    width: usize,
+    bits: Vec<u64>,
+}
+
+/// The map as tree bitsets, built once and shared by every slope counted on it.
+pub(super) struct TreeRows {
+    rows: Vec<TreeRow>,
+}
+
+impl TreeRows {
+    pub fn new(input: &[String]) -> Self {
+        let rows = input
+            .iter()
+            .map(|line| {
+                let mut row = TreeRow {
+                    width: 0,
+                    bits: Vec::new(),
+                };
+                for (i, c) in line.chars().enumerate() {
+                    if i % 64 == 0 {
+                        row.bits.push(0);
                     }
+                    if c == '#' {
+                        row.bits[i / 64] |= 1 << (i % 64);
+                    }
+                    row.width = i + 1;
                 }
+                row
+            })
+            .collect();
+        Self { rows }
+    }
+
+    /// Trees hit going down `slope` from the top left corner, the same as `ride` finds.
+    pub fn count(&self, slope: Slope) -> usize {
+        if slope.down < 0 {
+            return 0;
+        }
+        self.trees(slope.right as i128, slope.down as usize)
+    }
+
+    // Columns are kept unwrapped in 128 bits, like `path` does, as rows may differ in
+    // width. Steps up to 2^64 cannot overflow them before the rows run out.
+    fn trees(&self, right: i128, down: usize) -> usize {
+        if right == 0 && down == 0 {
+            return 0;
+        }
+        let (mut row, mut column) = (0_usize, 0_i128);
+        let mut trees = 0;
+        loop {
+            row = match row.checked_add(down) {
+                Some(row) => row,
+                None => return trees,
+            };
+            column += right;
+            let line = match self.rows.get(row) {
+                Some(line) if line.width > 0 => line,
+                _ => return trees,
+            };
+            let wrapped = column.rem_euclid(line.width as i128) as usize;
+            if down == 0 && wrapped == 0 {
+                return trees;
             }
-            acc
-        })
+            trees += (line.bits[wrapped / 64] >> (wrapped % 64) & 1) as usize;
+        }
+    }
+}
+
+/// Slopes found by `search_slopes`, with every slope ranked by the trees it hits.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub(super) struct SlopeSearch {
+    pub fewest: Vec<Slope>,
+    pub most: Vec<Slope>,
+    pub ranked: Vec<(Slope, usize)>,
+}
+
+impl fmt::Display for SlopeSearch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "rank right down trees")?;
+        for (i, (slope, trees)) in self.ranked.iter().enumerate() {
+            writeln!(
+                f,
+                "{:>4} {:>5} {:>4} {:>5}",
+                i + 1,
+                slope.right,
+                slope.down,
+                trees
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Counts the trees of every slope within the bounds going down. Slopes moving up or
+/// staying on the first row never reach the bottom of the map, so they are left out.
+///
+/// The ranking goes from the fewest trees to the most, ties in the order of `down`
+/// and then `right`.
+pub(super) fn search_slopes(
+    input: &[String],
+    right: RangeInclusive<i64>,
+    down: RangeInclusive<i64>,
+) -> SlopeSearch {
+    let rows = TreeRows::new(input);
+    let mut ranked: Vec<(Slope, usize)> = down
+        .filter(|d| *d > 0)
+        .flat_map(|d| right.clone().map(move |r| Slope::new(r, d)))
+        .map(|slope| (slope, rows.count(slope)))
+        .collect();
+    ranked.sort_by_key(|(slope, trees)| (*trees, slope.down, slope.right));
+    let with = |trees: Option<usize>| -> Vec<Slope> {
+        ranked
+            .iter()
+            .filter(|(_, t)| Some(*t) == trees)
+            .map(|(slope, _)| *slope)
+            .collect()
+    };
+    SlopeSearch {
+        fewest: with(ranked.first().map(|(_, t)| *t)),
+        most: with(ranked.last().map(|(_, t)| *t)),
+        ranked,
+    }
+}
+
+pub(super) fn tree_map(input: Vec<String>, line_step: usize, position_step: usize) -> usize {
+    TreeRows::new(&input).trees(position_step as i128, line_step)
 }
 
 pub(super) fn check_slots(input: Vec<String>, attempts: Vec<(usize, usize)>) -> usize {
-    attempts
-        .into_iter()
-        .fold(1, |acc, pair| acc * tree_map(input.clone(), pair.0, pair.1))
-}
-
-//..##.........##.........##.........##.........##.........##.......  --->
-//#..O#...#..#...#...#..#...#...#..#...#...#..#...#...#..#...#...#..
-//.#....X..#..#....#..#..#....#..#..#....#..#..#....#..#..#....#..#.
-//..#.#...#O#..#.#...#.#..#.#...#.#..#.#...#.#..#.#...#.#..#.#...#.#
-//.#...##..#..X...##..#..#...##..#..#...##..#..#...##..#..#...##..#.
-//..#.##.......#.X#.......#.##.......#.##.......#.##.......#.##.....  --->
-//.#.#.#....#.#.#.#.O..#.#.#.#....#.#.#.#....#.#.#.#....#.#.#.#....#
-//.#........#.#........X.#........#.#........#.#........#.#........#
-//#.##...#...#.##...#...#.X#...#...#.##...#...#.##...#...#.##...#...
-//#...##....##...##....##...#X....##...##....##...##....##...##....#
-//.#..#...#.#.#..#...#.#.#..#...X.#.#..#...#.#.#..#...#.#.#..#...#.#  --->
+    let rows = TreeRows::new(&input);
+    attempts.into_iter().fold(1, |acc, (down, right)| {
+        acc * rows.trees(right as i128, down)
+    })
+}
+
 #[test]
 fn test_tree_map() {
     let input = vec![
@@ -77,3 +422,276 @@ fn test_check_slots_map() {
     let pairs: Vec<(usize, usize)> = vec![(1, 1), (1, 3), (1, 5), (1, 7), (2, 1)];
     assert_eq!(336, check_slots(input, pairs))
 }
+
+#[test]
+fn test_slope_rational() {
+    assert_eq!(Some(Slope::new(3, 1)), Slope::rational((3, 1), (1, 1)));
+    assert_eq!(Some(Slope::new(1, 2)), Slope::rational((1, 2), (1, 1)));
+    assert_eq!(Some(Slope::new(3, 1)), Slope::rational((3, 2), (1, 2)));
+    assert_eq!(Some(Slope::new(-1, 2)), Slope::rational((1, -2), (2, 2)));
+    assert_eq!(Some(Slope::new(2, -3)), Slope::rational((4, 6), (-1, 1)));
+    assert_eq!(Some(Slope::new(0, 1)), Slope::rational((0, 5), (1, 1)));
+    assert_eq!(None, Slope::rational((1, 0), (1, 1)));
+    assert_eq!(None, Slope::rational((i64::MAX, 3), (1, 7)));
+    assert_eq!(None, Slope::rational((2, i64::MAX), (1, i64::MAX - 1)));
+    assert_eq!(
+        Some(Slope::new(i64::MAX - 1, i64::MAX)),
+        Slope::rational((1, i64::MAX), (1, i64::MAX - 1))
+    );
+    assert_eq!(
+        Some(Slope::new(1, 1)),
+        Slope::rational((i64::MIN, i64::MIN), (1, 1))
+    );
+}
+
+#[test]
+fn test_ride() {
+    let input = vec![
+        "..##.......".to_string(),
+        "#...#...#..".to_string(),
+        ".#....#..#.".to_string(),
+        "..#.#...#.#".to_string(),
+        ".#...##..#.".to_string(),
+    ];
+    let result = ride(&input, Slope::new(3, 1), (0, 0));
+    assert_eq!(vec![(1, 3), (2, 6), (3, 9), (4, 1)], result.visited);
+    assert_eq!(2, result.trees);
+    // Steps past twice the width wrap like any other
+    assert_eq!(result, ride(&input, Slope::new(3 + 22, 1), (0, 0)));
+    assert_eq!(result, ride(&input, Slope::new(3 - 33, 1), (0, 0)));
+    let result = ride(&input, Slope::new(-1, 2), (0, 0));
+    assert_eq!(vec![(2, 10), (4, 9)], result.visited);
+    assert_eq!(1, result.trees);
+    let result = ride(&input, Slope::new(-3, -1), (4, 1));
+    assert_eq!(vec![(3, 9), (2, 6), (1, 3), (0, 0)], result.visited);
+    assert_eq!(1, result.trees);
+    let half = Slope::rational((1, 2), (1, 1)).unwrap();
+    let result = ride(&input, half, (0, 0));
+    assert_eq!(vec![(2, 1), (4, 2)], result.visited);
+    assert_eq!(1, result.trees);
+}
+
+#[test]
+fn test_ride_edges() {
+    let input = vec!["..#.".to_string(), "#...".to_string()];
+    let result = ride(&input, Slope::new(2, 0), (0, 0));
+    assert_eq!(vec![(0, 2)], result.visited);
+    assert_eq!(1, result.trees);
+    let result = ride(&input, Slope::new(-1, 0), (1, 1));
+    assert_eq!(vec![(1, 0), (1, 3), (1, 2)], result.visited);
+    assert_eq!(Ride::default(), ride(&input, Slope::new(0, 0), (0, 0)));
+    assert_eq!(Ride::default(), ride(&input, Slope::new(1, -1), (0, 0)));
+    assert_eq!(Ride::default(), ride(&[], Slope::new(1, 1), (0, 0)));
+    assert_eq!(1, tree_map(input, 0, 1));
+}
+
+#[test]
+fn test_render_path() {
+    let input = vec![
+        "..##.......".to_string(),
+        "#...#...#..".to_string(),
+        ".#....#..#.".to_string(),
+        "..#.#...#.#".to_string(),
+        ".#...##..#.".to_string(),
+        "..#.##.....".to_string(),
+        ".#.#.#....#".to_string(),
+        ".#........#".to_string(),
+        "#.##...#...".to_string(),
+        "#...##....#".to_string(),
+        ".#..#...#.#".to_string(),
+    ];
+    let expected = vec![
+        "..##.........##.........##.......",
+        "#..O#...#..#...#...#..#...#...#..",
+        ".#....X..#..#....#..#..#....#..#.",
+        "..#.#...#O#..#.#...#.#..#.#...#.#",
+        ".#...##..#..X...##..#..#...##..#.",
+        "..#.##.......#.X#.......#.##.....",
+        ".#.#.#....#.#.#.#.O..#.#.#.#....#",
+        ".#........#.#........X.#........#",
+        "#.##...#...#.##...#...#.X#...#...",
+        "#...##....##...##....##...#X....#",
+        ".#..#...#.#.#..#...#.#.#..#...X.#",
+        "",
+    ];
+    assert_eq!(
+        expected.join("\n"),
+        render_path(&input, Slope::new(3, 1), (0, 0)).unwrap()
+    );
+    let rendered = render_path(&input[..3], Slope::new(-2, 1), (0, 0)).unwrap();
+    assert_eq!(
+        vec![
+            "..##.........##.......",
+            "#...#...#O.#...#...#..",
+            ".#....#O.#..#....#..#."
+        ],
+        rendered.lines().collect::<Vec<&str>>()
+    );
+    assert_eq!(
+        Some(String::new()),
+        render_path(&[], Slope::new(1, 1), (0, 0))
+    );
+}
+
+#[test]
+fn test_render_svg() {
+    let input = vec!["..#".to_string(), "#..".to_string(), ".#.".to_string()];
+    let svg = render_svg(&input, &[Slope::new(1, 1), Slope::new(2, 1)]).unwrap();
+    assert!(
+        svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"60\" height=\"30\">")
+    );
+    assert!(svg.ends_with("</svg>\n"));
+    // Three trees in each of the two copies of the map
+    assert_eq!(6, svg.matches("fill=\"#2f4f2f\"").count());
+    assert!(svg.contains("<polyline points=\"5,5 15,15 25,25\" fill=\"none\" stroke=\"#e6194b\""));
+    assert!(svg.contains("<polyline points=\"5,5 25,15 45,25\" fill=\"none\" stroke=\"#3cb44b\""));
+    // Only the second slope hits a tree, at row 2 column 4
+    assert_eq!(1, svg.matches("height=\"10\" fill=\"none\"").count());
+    assert!(svg.contains(
+        "<rect x=\"40\" y=\"20\" width=\"10\" height=\"10\" fill=\"none\" stroke=\"#3cb44b\""
+    ));
+}
+
+#[test]
+fn test_tree_rows() {
+    let mut input = vec![
+        "..##.......".to_string(),
+        "#...#...#..".to_string(),
+        ".#....#..#.".to_string(),
+        "..#.#...#.#".to_string(),
+        ".#...##..#.".to_string(),
+    ];
+    input.push(format!("{}#", ".".repeat(69)));
+    input.push("#".repeat(70));
+    let rows = TreeRows::new(&input);
+    for right in -9..=9 {
+        for down in -1..=4 {
+            let slope = Slope::new(right, down);
+            assert_eq!(ride(&input, slope, (0, 0)).trees, rows.count(slope));
+        }
+    }
+    assert_eq!(0, TreeRows::new(&[]).count(Slope::new(1, 1)));
+}
+
+#[test]
+fn test_search_slopes() {
+    let input = vec![
+        "..##.......".to_string(),
+        "#...#...#..".to_string(),
+        ".#....#..#.".to_string(),
+        "..#.#...#.#".to_string(),
+        ".#...##..#.".to_string(),
+        "..#.##.....".to_string(),
+        ".#.#.#....#".to_string(),
+        ".#........#".to_string(),
+        "#.##...#...".to_string(),
+        "#...##....#".to_string(),
+        ".#..#...#.#".to_string(),
+    ];
+    let search = search_slopes(&input, 1..=7, 1..=2);
+    assert_eq!(14, search.ranked.len());
+    assert!(search.ranked.contains(&(Slope::new(3, 1), 7)));
+    assert!(search.ranked.contains(&(Slope::new(1, 2), 2)));
+    let trees: Vec<usize> = search.ranked.iter().map(|(_, trees)| *trees).collect();
+    assert!(trees.windows(2).all(|pair| pair[0] <= pair[1]));
+    assert_eq!(vec![search.ranked[0].0], search.fewest);
+    assert_eq!(Some(&(Slope::new(3, 1), 7)), search.ranked.last());
+    assert_eq!(vec![Slope::new(3, 1)], search.most);
+    let table = search.to_string();
+    assert!(table.starts_with("rank right down trees\n"));
+    assert_eq!(15, table.lines().count());
+    let search = search_slopes(&input, 0..=0, 0..=0);
+    assert_eq!(SlopeSearch::default(), search);
+    let search = search_slopes(&input, -2..=2, -2..=2);
+    assert_eq!(10, search.ranked.len());
+    assert!(search.ranked.iter().all(|(slope, _)| slope.down > 0));
+    let expected = search_slopes(&input, -2..=2, 1..=2);
+    assert_eq!(expected, search);
+    assert_eq!(
+        vec![Slope::new(2, 1), Slope::new(0, 2), Slope::new(2, 2)],
+        search.fewest
+    );
+}
+
+#[test]
+fn test_render_ragged_map() {
+    let input = vec![
+        "..#.".to_string(),
+        "#.".to_string(),
+        "".to_string(),
+        "...#".to_string(),
+    ];
+    assert_eq!(
+        vec!["..#.", "#.#O", "", "...#"],
+        render_path(&input, Slope::new(3, 1), (0, 0))
+            .unwrap()
+            .lines()
+            .collect::<Vec<&str>>()
+    );
+    let ride = ride(&input, Slope::new(3, 1), (0, 0));
+    assert_eq!(vec![(1, 1)], ride.visited);
+    let svg = render_svg(&input, &[Slope::new(3, 1), Slope::new(2, 1)]).unwrap();
+    assert!(
+        svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"40\" height=\"40\">")
+    );
+    // Row 1 wraps on its own two columns, so slope (2, 1) hits its tree at column 2
+    assert!(svg.contains(
+        "<rect x=\"20\" y=\"10\" width=\"10\" height=\"10\" fill=\"none\" stroke=\"#3cb44b\""
+    ));
+}
+
+#[test]
+fn test_ride_huge_steps() {
+    let input = vec![
+        "..#..".to_string(),
+        "#...#".to_string(),
+        "...#.".to_string(),
+    ];
+    // 2^62 is 4 and i64::MAX is 2 modulo the width of 5
+    let result = ride(&input, Slope::new(i64::MAX / 2 + 1, 1), (0, 0));
+    assert_eq!(vec![(1, 4), (2, 3)], result.visited);
+    assert_eq!(2, result.trees);
+    let result = ride(&input, Slope::new(i64::MAX, 1), (0, 0));
+    assert_eq!(vec![(1, 2), (2, 4)], result.visited);
+    let result = ride(&input, Slope::new(i64::MIN, 1), (0, 0));
+    assert_eq!(vec![(1, 2), (2, 4)], result.visited);
+    assert_eq!(
+        Ride::default(),
+        ride(&input, Slope::new(0, i64::MAX), (1, 0))
+    );
+    let slope = Slope::rational((1, i64::MAX), (1, i64::MAX - 1)).unwrap();
+    assert_eq!(Ride::default(), ride(&input, slope, (0, 0)));
+    // Along a row every column but the starting one comes up once
+    let result = ride(&input, Slope::new(i64::MAX, 0), (1, 0));
+    assert_eq!(4, result.visited.len());
+}
+
+#[test]
+fn test_render_too_wide() {
+    let input = vec!["..#".to_string(), "#..".to_string(), ".#.".to_string()];
+    let slope = Slope::new(100_000, 1);
+    assert_eq!(None, render_path(&input, slope, (0, 0)));
+    assert_eq!(None, render_svg(&input, &[Slope::new(1, 1), slope]));
+    assert_eq!(None, render_path(&input, Slope::new(i64::MIN, 1), (0, 0)));
+    assert!(render_path(&input, Slope::new(-1_000, 1), (0, 0)).is_some());
+}
+
+#[test]
+fn test_count_huge_steps() {
+    let input = vec![
+        "..#..".to_string(),
+        "#...#".to_string(),
+        "...#.".to_string(),
+    ];
+    let rows = TreeRows::new(&input);
+    for right in [i64::MAX, i64::MIN, i64::MAX / 2 + 1] {
+        let slope = Slope::new(right, 1);
+        assert_eq!(ride(&input, slope, (0, 0)).trees, rows.count(slope));
+    }
+    assert_eq!(0, rows.count(Slope::new(1, i64::MAX)));
+    // usize::MAX is 0 modulo the width of 5, so the path lands on column 0 of both rows
+    assert_eq!(1, tree_map(input.clone(), 1, usize::MAX));
+    assert_eq!(0, tree_map(input.clone(), usize::MAX, 1));
+    assert_eq!(0, check_slots(input, vec![(1, 1), (usize::MAX, 1)]));
+    assert!(Slope::try_from((1, usize::MAX)).is_err());
+}
@@ -0,0 +1,65 @@
+mod first_day;
+mod fourth_day;
+mod second_day;
+mod third_day;
+
+use first_day::*;
+use fourth_day::*;
+use second_day::*;
+use third_day::*;
+
+use super::lines_from_file;
+
+pub fn run_fourth_week() {
+    run_first_day();
+    run_second_day();
+    run_third_day();
+    run_fourth_day();
+}
+
+fn run_first_day() {
+    let input = lines_from_file("inputs/fourth_week/day1.txt").expect("Could not load lines");
+    match combat_score(input.clone(), false) {
+        Some(n) => println!("The winning player's score is {}", n),
+        None => println!("The game of combat never ends"),
+    }
+    match combat_score(input, true) {
+        Some(n) => println!("The winning player's recursive score is {}", n),
+        None => println!("The game of recursive combat never ends"),
+    }
+}
+
+fn run_second_day() {
+    let input = lines_from_file("inputs/fourth_week/day2.txt").expect("Could not load lines");
+    match play_cups(input.clone(), 9, 100) {
+        Ok(cups) => println!("The labels after cup 1 are {}", cups.labels_after_one()),
+        Err(e) => println!("Could not play the cups: {:?}", e),
+    }
+    match play_cups(input, 1_000_000, 10_000_000) {
+        Ok(cups) => println!(
+            "The product of the cups holding the stars is {}",
+            cups.product_after_one()
+        ),
+        Err(e) => println!("Could not play the million cups: {:?}", e),
+    }
+}
+
+fn run_third_day() {
+    let input = lines_from_file("inputs/fourth_week/day3.txt").expect("Could not load lines");
+    match black_tiles(input.clone(), 0) {
+        Ok(n) => println!("Tiles left with the black side up: {}", n),
+        Err(e) => println!("Could not follow the tile paths: {:?}", e),
+    }
+    match black_tiles(input, 100) {
+        Ok(n) => println!("Black tiles after 100 days: {}", n),
+        Err(e) => println!("Could not follow the tile paths: {:?}", e),
+    }
+}
+
+fn run_fourth_day() {
+    let input = lines_from_file("inputs/fourth_week/day4.txt").expect("Could not load lines");
+    match encryption_key(input, 7, 20201227) {
+        Some(key) => println!("The encryption key is {}", key),
+        None => println!("The public keys do not share a subject number"),
+    }
+}
@@ -0,0 +1,240 @@
+use std::collections::{HashSet, VecDeque};
+use std::fmt;
+
+type Deck = VecDeque<u32>;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Player {
+    One,
+    Two,
+}
+
+/// One round as the puzzle narrates it, with the decks before the cards are drawn.
+#[derive(Debug, Eq, PartialEq)]
+pub struct Round {
+    game: usize,
+    round: usize,
+    decks: (Vec<u32>, Vec<u32>),
+    winner: Player,
+}
+
+impl fmt::Display for Round {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let join = |deck: &[u32]| {
+            deck.iter()
+                .map(|c| c.to_string())
+                .collect::<Vec<String>>()
+                .join(", ")
+        };
+        writeln!(f, "-- Round {} (Game {}) --", self.round, self.game)?;
+        writeln!(f, "Player 1's deck: {}", join(&self.decks.0))?;
+        writeln!(f, "Player 2's deck: {}", join(&self.decks.1))?;
+        let winner = if self.winner == Player::One { 1 } else { 2 };
+        write!(
+            f,
+            "Player {} wins round {} of game {}!",
+            winner, self.round, self.game
+        )
+    }
+}
+
+struct Combat {
+    recursive: bool,
+    games: usize,
+    log: Option<Vec<Round>>,
+}
+
+impl Combat {
+    fn new(recursive: bool, log: bool) -> Self {
+        Self {
+            recursive,
+            games: 0,
+            log: if log { Some(Vec::new()) } else { None },
+        }
+    }
+
+    /// The winner and their deck, `None` when a plain game repeats a previous state
+    /// and so would go on forever.
+    fn play(&mut self, mut one: Deck, mut two: Deck) -> Option<(Player, Deck)> {
+        self.games += 1;
+        let game = self.games;
+        // Whole states rather than digests, a hash collision must not end the game
+        let mut seen: HashSet<(Deck, Deck)> = HashSet::new();
+        let mut round = 0;
+        while !one.is_empty() && !two.is_empty() {
+            // Repeating a previous state of a recursive game means player one wins it
+            if !seen.insert((one.clone(), two.clone())) {
+                return if self.recursive {
+                    Some((Player::One, one))
+                } else {
+                    None
+                };
+            }
+            round += 1;
+            let decks = self
+                .log
+                .as_ref()
+                .map(|_| (one.iter().cloned().collect(), two.iter().cloned().collect()));
+            let (a, b) = (one.pop_front().unwrap(), two.pop_front().unwrap());
+            let winner = if self.recursive && one.len() >= a as usize && two.len() >= b as usize {
+                let sub_one = one.iter().take(a as usize).cloned().collect();
+                let sub_two = two.iter().take(b as usize).cloned().collect();
+                self.play(sub_one, sub_two)?.0
+            } else if a > b {
+                Player::One
+            } else {
+                Player::Two
+            };
+            if let (Some(log), Some(decks)) = (self.log.as_mut(), decks) {
+                log.push(Round {
+                    game,
+                    round,
+                    decks,
+                    winner,
+                });
+            }
+            match winner {
+                Player::One => one.extend([a, b].iter()),
+                Player::Two => two.extend([b, a].iter()),
+            }
+        }
+        if one.is_empty() {
+            Some((Player::Two, two))
+        } else {
+            Some((Player::One, one))
+        }
+    }
+}
+
+fn create_decks(input: Vec<String>) -> (Deck, Deck) {
+    let mut sections = input.split(|s| s.is_empty());
+    let mut deck = || -> Deck {
+        sections
+            .next()
+            .map(|s| s.iter().skip(1).map(|c| c.parse().unwrap_or(0)).collect())
+            .unwrap_or_default()
+    };
+    let one = deck();
+    (one, deck())
+}
+
+fn score(deck: &Deck) -> u64 {
+    deck.iter()
+        .rev()
+        .enumerate()
+        .map(|(i, c)| (i as u64 + 1) * *c as u64)
+        .sum()
+}
+
+/// Score of the winning deck, `None` when a plain game loops forever.
+pub fn combat_score(input: Vec<String>, recursive: bool) -> Option<u64> {
+    let (one, two) = create_decks(input);
+    let (_, deck) = Combat::new(recursive, false).play(one, two)?;
+    Some(score(&deck))
+}
+
+/// Plays the game recording every round, sub-games included, up to a loop if any.
+#[allow(dead_code)]
+pub fn combat_log(input: Vec<String>, recursive: bool) -> (Option<u64>, Vec<Round>) {
+    let (one, two) = create_decks(input);
+    let mut combat = Combat::new(recursive, true);
+    let result = combat.play(one, two).map(|(_, deck)| score(&deck));
+    (result, combat.log.unwrap_or_default())
+}
+
+#[test]
+fn test_create_decks() {
+    let input = vec![
+        "Player 1:".to_string(),
+        "9".to_string(),
+        "2".to_string(),
+        "6".to_string(),
+        "3".to_string(),
+        "1".to_string(),
+        "".to_string(),
+        "Player 2:".to_string(),
+        "5".to_string(),
+        "8".to_string(),
+        "4".to_string(),
+        "7".to_string(),
+        "10".to_string(),
+    ];
+    let (one, two) = create_decks(input);
+    assert_eq!(vec![9, 2, 6, 3, 1], one.into_iter().collect::<Vec<u32>>());
+    assert_eq!(vec![5, 8, 4, 7, 10], two.into_iter().collect::<Vec<u32>>());
+}
+
+#[test]
+fn test_combat_score() {
+    let input = vec![
+        "Player 1:".to_string(),
+        "9".to_string(),
+        "2".to_string(),
+        "6".to_string(),
+        "3".to_string(),
+        "1".to_string(),
+        "".to_string(),
+        "Player 2:".to_string(),
+        "5".to_string(),
+        "8".to_string(),
+        "4".to_string(),
+        "7".to_string(),
+        "10".to_string(),
+    ];
+    assert_eq!(Some(306), combat_score(input.clone(), false));
+    assert_eq!(Some(291), combat_score(input, true));
+}
+
+#[test]
+fn test_recursive_combat_loop() {
+    let input = vec![
+        "Player 1:".to_string(),
+        "43".to_string(),
+        "19".to_string(),
+        "".to_string(),
+        "Player 2:".to_string(),
+        "2".to_string(),
+        "29".to_string(),
+        "14".to_string(),
+    ];
+    assert_eq!(Some(105), combat_score(input.clone(), true));
+    assert_eq!(None, combat_score(input.clone(), false));
+    let (score, log) = combat_log(input, false);
+    assert_eq!(None, score);
+    assert_eq!((vec![43, 19], vec![2, 29, 14]), log[0].decks);
+}
+
+#[test]
+fn test_combat_log() {
+    let input = vec![
+        "Player 1:".to_string(),
+        "9".to_string(),
+        "2".to_string(),
+        "6".to_string(),
+        "3".to_string(),
+        "1".to_string(),
+        "".to_string(),
+        "Player 2:".to_string(),
+        "5".to_string(),
+        "8".to_string(),
+        "4".to_string(),
+        "7".to_string(),
+        "10".to_string(),
+    ];
+    let (score, log) = combat_log(input.clone(), false);
+    assert_eq!(Some(306), score);
+    assert_eq!(29, log.len());
+    assert_eq!(
+        "-- Round 1 (Game 1) --\n\
+         Player 1's deck: 9, 2, 6, 3, 1\n\
+         Player 2's deck: 5, 8, 4, 7, 10\n\
+         Player 1 wins round 1 of game 1!",
+        log[0].to_string()
+    );
+    let (score, log) = combat_log(input, true);
+    assert_eq!(Some(291), score);
+    assert_eq!(5, log.iter().map(|r| r.game).max().unwrap());
+    let round = log.iter().find(|r| r.game == 2).unwrap();
+    assert_eq!((vec![9, 8, 5, 2], vec![10, 1, 7]), round.decks);
+    assert_eq!(Player::Two, round.winner);
+}
@@ -0,0 +1,35 @@
+use crate::number_theory::{discrete_log, mod_pow};
+
+fn public_keys(input: Vec<String>) -> (u64, u64) {
+    let mut keys = input.iter().map(|s| s.trim().parse().unwrap_or(0));
+    (keys.next().unwrap_or(0), keys.next().unwrap_or(0))
+}
+
+/// Loop size that turns `subject` into `public_key`, `None` if no loop size does.
+fn loop_size(public_key: u64, subject: u64, modulus: u64) -> Option<u64> {
+    discrete_log(subject, public_key, modulus)
+}
+
+pub fn encryption_key(input: Vec<String>, subject: u64, modulus: u64) -> Option<u64> {
+    let (card, door) = public_keys(input);
+    let card_loop = loop_size(card, subject, modulus)?;
+    Some(mod_pow(door, card_loop, modulus))
+}
+
+#[test]
+fn test_loop_size() {
+    assert_eq!(Some(8), loop_size(5764801, 7, 20201227));
+    assert_eq!(Some(11), loop_size(17807724, 7, 20201227));
+}
+
+#[test]
+fn test_encryption_key() {
+    let input = vec!["5764801".to_string(), "17807724".to_string()];
+    assert_eq!(Some(14897079), encryption_key(input, 7, 20201227));
+    let input = vec!["17807724".to_string(), "5764801".to_string()];
+    assert_eq!(Some(14897079), encryption_key(input, 7, 20201227));
+    let input = vec!["3".to_string(), "4".to_string()];
+    assert_eq!(None, encryption_key(input.clone(), 2, 7));
+    assert_eq!(None, encryption_key(input.clone(), 7, 0));
+    assert_eq!(None, encryption_key(input, 7, 1));
+}
@@ -0,0 +1,150 @@
+#[derive(Debug, Eq, PartialEq)]
+pub enum CupsError {
+    /// The input labels are not exactly the numbers from 1 up to their count.
+    NotPermutation,
+    /// Fewer cups than input labels, or fewer than the four a move needs.
+    TooFewCups(usize),
+}
+
+/// Circle of cups stored as a successor array, `next[label]` is the cup clockwise of `label`.
+pub struct Cups {
+    next: Vec<usize>,
+    current: usize,
+}
+
+impl Cups {
+    // Labels from the input go first, then every label up to `total` in order.
+    fn new(inp: &str, total: usize) -> Result<Self, CupsError> {
+        let given = inp
+            .trim()
+            .chars()
+            .map(|c| c.to_digit(10).map(|d| d as usize))
+            .collect::<Option<Vec<usize>>>()
+            .ok_or(CupsError::NotPermutation)?;
+        let mut sorted = given.clone();
+        sorted.sort_unstable();
+        if sorted.iter().enumerate().any(|(i, label)| *label != i + 1) {
+            return Err(CupsError::NotPermutation);
+        }
+        if total < given.len().max(4) {
+            return Err(CupsError::TooFewCups(total));
+        }
+        let labels: Vec<usize> = given
+            .iter()
+            .cloned()
+            .chain(given.len() + 1..=total)
+            .collect();
+        let mut next = vec![0; labels.len() + 1];
+        labels
+            .iter()
+            .zip(labels.iter().cycle().skip(1))
+            .for_each(|(label, following)| next[*label] = *following);
+        Ok(Self {
+            next,
+            current: labels[0],
+        })
+    }
+
+    fn step(&mut self) {
+        let max = self.next.len() - 1;
+        let first = self.next[self.current];
+        let second = self.next[first];
+        let third = self.next[second];
+        self.next[self.current] = self.next[third];
+        let mut destination = self.current;
+        loop {
+            destination = if destination == 1 {
+                max
+            } else {
+                destination - 1
+            };
+            if destination != first && destination != second && destination != third {
+                break;
+            }
+        }
+        self.next[third] = self.next[destination];
+        self.next[destination] = first;
+        self.current = self.next[self.current];
+    }
+
+    /// Labels clockwise of cup 1, without cup 1 itself.
+    pub fn labels_after_one(&self) -> String {
+        let mut labels = String::new();
+        let mut cup = self.next[1];
+        while cup != 1 {
+            labels.push_str(&cup.to_string());
+            cup = self.next[cup];
+        }
+        labels
+    }
+
+    /// Product of the two cups clockwise of cup 1.
+    pub fn product_after_one(&self) -> u64 {
+        let first = self.next[1];
+        first as u64 * self.next[first] as u64
+    }
+}
+
+pub fn play_cups(input: Vec<String>, cups: usize, moves: usize) -> Result<Cups, CupsError> {
+    let inp = input.first().cloned().unwrap_or_default();
+    let mut cups = Cups::new(&inp, cups)?;
+    (0..moves).for_each(|_| cups.step());
+    Ok(cups)
+}
+
+#[test]
+fn test_new_cups() {
+    let cups = Cups::new("389125467", 9).unwrap();
+    assert_eq!(vec![0, 2, 5, 8, 6, 4, 7, 3, 9, 1], cups.next);
+    assert_eq!(3, cups.current);
+    let cups = Cups::new("389125467", 12).unwrap();
+    assert_eq!(10, cups.next[7]);
+    assert_eq!(3, cups.next[12]);
+    let cups = Cups::new("2134", 4).unwrap();
+    assert_eq!(vec![0, 3, 1, 4, 2], cups.next);
+}
+
+#[test]
+fn test_invalid_cups() {
+    assert_eq!(
+        Some(CupsError::TooFewCups(5)),
+        Cups::new("389125467", 5).err()
+    );
+    assert_eq!(Some(CupsError::TooFewCups(3)), Cups::new("312", 3).err());
+    assert_eq!(Some(CupsError::TooFewCups(0)), Cups::new("", 0).err());
+    assert_eq!(Some(CupsError::NotPermutation), Cups::new("3891", 9).err());
+    assert_eq!(Some(CupsError::NotPermutation), Cups::new("1223", 9).err());
+    assert_eq!(Some(CupsError::NotPermutation), Cups::new("12a3", 9).err());
+    assert_eq!(Some(CupsError::NotPermutation), Cups::new("0123", 9).err());
+    assert_eq!(
+        Some(CupsError::TooFewCups(5)),
+        play_cups(vec!["389125467".to_string()], 5, 1).err()
+    );
+}
+
+#[test]
+fn test_step() {
+    let mut cups = Cups::new("389125467", 9).unwrap();
+    cups.step();
+    assert_eq!("54673289", cups.labels_after_one());
+    assert_eq!(2, cups.current);
+}
+
+#[test]
+fn test_play_cups() {
+    let input = vec!["389125467".to_string()];
+    assert_eq!(
+        "92658374",
+        play_cups(input.clone(), 9, 10).unwrap().labels_after_one()
+    );
+    assert_eq!(
+        "67384529",
+        play_cups(input.clone(), 9, 100).unwrap().labels_after_one()
+    );
+    assert_eq!(
+        149245887792,
+        play_cups(input, 1_000_000, 10_000_000)
+            .unwrap()
+            .product_after_one()
+    );
+}
@@ -0,0 +1,114 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::hex::{Direction, Hex};
+
+/// A line that is not a path of directions, `line` counts from 1.
+#[derive(Debug, Eq, PartialEq)]
+pub struct PathError {
+    line: usize,
+    path: String,
+}
+
+// Flips the tile at the end of every path, the ones flipped an odd number of times stay black.
+// Blank lines are skipped, they would flip the reference tile otherwise.
+fn create_floor(input: Vec<String>) -> Result<HashSet<Hex>, PathError> {
+    let mut black = HashSet::new();
+    for (i, s) in input.iter().enumerate() {
+        if s.trim().is_empty() {
+            continue;
+        }
+        let path = Direction::parse_path(s).ok_or_else(|| PathError {
+            line: i + 1,
+            path: s.clone(),
+        })?;
+        let tile = Hex::default().walk(&path);
+        if !black.remove(&tile) {
+            black.insert(tile);
+        }
+    }
+    Ok(black)
+}
+
+fn flip_day(black: &HashSet<Hex>) -> HashSet<Hex> {
+    let mut counts: HashMap<Hex, usize> = HashMap::new();
+    black
+        .iter()
+        .flat_map(|tile| tile.neighbours().collect::<Vec<Hex>>())
+        .for_each(|n| *counts.entry(n).or_insert(0) += 1);
+    counts
+        .into_iter()
+        .filter(|(tile, count)| *count == 2 || (*count == 1 && black.contains(tile)))
+        .map(|(tile, _)| tile)
+        .collect()
+}
+
+pub fn black_tiles(input: Vec<String>, days: usize) -> Result<usize, PathError> {
+    Ok((0..days)
+        .fold(create_floor(input)?, |black, _| flip_day(&black))
+        .len())
+}
+
+#[test]
+fn test_create_floor() {
+    let input = vec![
+        "esew".to_string(),
+        "nwwswee".to_string(),
+        "esew".to_string(),
+    ];
+    let black = create_floor(input).unwrap();
+    assert_eq!(1, black.len());
+    assert!(black.contains(&Hex::default()));
+    let input = vec!["e".to_string(), "".to_string(), "esx".to_string()];
+    assert_eq!(
+        Err(PathError {
+            line: 3,
+            path: "esx".to_string()
+        }),
+        create_floor(input)
+    );
+}
+
+#[test]
+fn test_flip_day() {
+    let input = vec!["e".to_string(), "w".to_string(), "ne".to_string()];
+    let black = flip_day(&create_floor(input).unwrap());
+    assert_eq!(4, black.len());
+    assert!(black.contains(&Hex::new(1, -1)));
+    assert!(black.contains(&Hex::new(2, -1)));
+    assert!(!black.contains(&Hex::new(-1, 0)));
+    assert!(!black.contains(&Hex::default()));
+}
+
+#[test]
+fn test_black_tiles() {
+    let input = vec![
+        "wnwseseseseseneneeneseneswneew".to_string(),
+        "neneeenwsewnewneswwe".to_string(),
+        "swnwseneswswenwneswneeseswswne".to_string(),
+        "swesenenwsew".to_string(),
+        "swswnwneswseenwwseneseswwsenenwsew".to_string(),
+        "swnwsenwseeneseswswnenwnese".to_string(),
+        "seswwewswswswsenenww".to_string(),
+        "sesweewneswwwee".to_string(),
+        "swnewwnwwnw".to_string(),
+        "wwseswswswswseswneenwnee".to_string(),
+        "swnwenenewwnewswswwwneswswneewe".to_string(),
+        "wesesesenwnenwsewsesee".to_string(),
+        "eeseenwnew".to_string(),
+        "seswswesee".to_string(),
+        "nwenwnwwnwnesw".to_string(),
+        "nenewwnewwnenese".to_string(),
+        "esenwsesesewswswsene".to_string(),
+        "seseeswnenenwnwseswwesesew".to_string(),
+        "nwwnwswwneseeseseseneseese".to_string(),
+        "swwnenwnenwseswswse".to_string(),
+    ];
+    assert_eq!(Ok(16), black_tiles(input.clone(), 0));
+    assert_eq!(Ok(26), black_tiles(input.clone(), 1));
+    assert_eq!(Ok(66), black_tiles(input.clone(), 10));
+    assert_eq!(Ok(2333), black_tiles(input.clone(), 100));
+    let mut spaced = input;
+    spaced.insert(5, "".to_string());
+    spaced.push("".to_string());
+    assert_eq!(Ok(16), black_tiles(spaced, 0));
+}
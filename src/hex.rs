@@ -0,0 +1,120 @@
+/// Hexagonal grid with pointy-top tiles in axial coordinates: `q` grows east, `r` grows south-east.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+pub struct Hex {
+    pub q: i32,
+    pub r: i32,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Direction {
+    East,
+    SouthEast,
+    SouthWest,
+    West,
+    NorthWest,
+    NorthEast,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 6] = [
+        Direction::East,
+        Direction::SouthEast,
+        Direction::SouthWest,
+        Direction::West,
+        Direction::NorthWest,
+        Direction::NorthEast,
+    ];
+
+    fn offset(&self) -> (i32, i32) {
+        match self {
+            Direction::East => (1, 0),
+            Direction::SouthEast => (0, 1),
+            Direction::SouthWest => (-1, 1),
+            Direction::West => (-1, 0),
+            Direction::NorthWest => (0, -1),
+            Direction::NorthEast => (1, -1),
+        }
+    }
+
+    /// Splits an unseparated `e/se/sw/w/nw/ne` string, `None` if it holds anything else.
+    pub fn parse_path(inp: &str) -> Option<Vec<Direction>> {
+        let mut path = Vec::new();
+        let mut chars = inp.trim().chars();
+        while let Some(c) = chars.next() {
+            let direction = match c {
+                'e' => Direction::East,
+                'w' => Direction::West,
+                's' => match chars.next()? {
+                    'e' => Direction::SouthEast,
+                    'w' => Direction::SouthWest,
+                    _ => return None,
+                },
+                'n' => match chars.next()? {
+                    'e' => Direction::NorthEast,
+                    'w' => Direction::NorthWest,
+                    _ => return None,
+                },
+                _ => return None,
+            };
+            path.push(direction);
+        }
+        Some(path)
+    }
+}
+
+impl Hex {
+    pub fn new(q: i32, r: i32) -> Self {
+        Self { q, r }
+    }
+
+    pub fn neighbour(&self, direction: Direction) -> Self {
+        let (dq, dr) = direction.offset();
+        Self::new(self.q + dq, self.r + dr)
+    }
+
+    pub fn neighbours(&self) -> impl Iterator<Item = Hex> + '_ {
+        Direction::ALL.iter().map(move |d| self.neighbour(*d))
+    }
+
+    /// The tile reached by following `path` from this one.
+    pub fn walk(&self, path: &[Direction]) -> Self {
+        path.iter().fold(*self, |hex, d| hex.neighbour(*d))
+    }
+
+    /// Number of steps between two tiles.
+    #[allow(dead_code)]
+    pub fn distance(&self, other: &Hex) -> i32 {
+        let (dq, dr) = (self.q - other.q, self.r - other.r);
+        (dq.abs() + dr.abs() + (dq + dr).abs()) / 2
+    }
+}
+
+#[test]
+fn test_parse_path() {
+    assert_eq!(
+        Some(vec![Direction::East, Direction::SouthEast, Direction::West]),
+        Direction::parse_path("esew")
+    );
+    assert_eq!(Some(Vec::new()), Direction::parse_path(""));
+    assert_eq!(None, Direction::parse_path("ex"));
+    assert_eq!(None, Direction::parse_path("es"));
+}
+
+#[test]
+fn test_walk() {
+    let origin = Hex::default();
+    let path = Direction::parse_path("esew").unwrap();
+    assert_eq!(origin.neighbour(Direction::SouthEast), origin.walk(&path));
+    let path = Direction::parse_path("nwwswee").unwrap();
+    assert_eq!(origin, origin.walk(&path));
+}
+
+#[test]
+fn test_neighbours() {
+    let hex = Hex::new(2, -1);
+    let neighbours: Vec<Hex> = hex.neighbours().collect();
+    assert_eq!(6, neighbours.len());
+    assert!(neighbours.iter().all(|n| hex.distance(n) == 1));
+    assert!(neighbours.contains(&Hex::new(3, -2)));
+    assert_eq!(3, Hex::default().distance(&Hex::new(2, -3)));
+}
@@ -1,8 +1,12 @@
 mod first_week;
+mod fourth_week;
+mod hex;
+mod number_theory;
 mod second_week;
+mod third_week;
 
 use std::{
-    fs::File,
+    fs::{self, File},
     io::{BufRead, BufReader, Result},
     path::Path,
 };
@@ -10,8 +14,18 @@ use std::{
 fn main() {
     first_week::run_first_week();
     second_week::run_second_week();
+    third_week::run_third_week();
+    fourth_week::run_fourth_week();
 }
 
 pub fn lines_from_file(filename: impl AsRef<Path>) -> Result<Vec<String>> {
     BufReader::new(File::open(filename)?).lines().collect()
 }
+
+/// Writes a report, creating the directories leading to it.
+pub fn write_output(filename: impl AsRef<Path>, contents: &str) -> Result<()> {
+    if let Some(parent) = filename.as_ref().parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(filename, contents)
+}
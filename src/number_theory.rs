@@ -0,0 +1,158 @@
+use std::collections::HashMap;
+
+fn mul_mod(a: u64, b: u64, modulus: u64) -> u64 {
+    (a as u128 * b as u128 % modulus as u128) as u64
+}
+
+/// Greatest common divisor, `gcd(0, 0)` is 0.
+pub fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// `base^exp mod modulus` by repeated squaring, `modulus` must not be 0.
+pub fn mod_pow(base: u64, exp: u64, modulus: u64) -> u64 {
+    if modulus == 1 {
+        return 0;
+    }
+    let mut result = 1;
+    let mut base = base % modulus;
+    let mut exp = exp;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_mod(result, base, modulus);
+        }
+        base = mul_mod(base, base, modulus);
+        exp >>= 1;
+    }
+    result
+}
+
+/// Inverse of `value` modulo `modulus`, if they are coprime.
+pub fn mod_inverse(value: u64, modulus: u64) -> Option<u64> {
+    if modulus == 0 {
+        return None;
+    }
+    let (mut old_r, mut r) = (value as i128 % modulus as i128, modulus as i128);
+    let (mut old_s, mut s) = (1_i128, 0_i128);
+    while r != 0 {
+        let quotient = old_r / r;
+        let next_r = old_r - quotient * r;
+        old_r = r;
+        r = next_r;
+        let next_s = old_s - quotient * s;
+        old_s = s;
+        s = next_s;
+    }
+    if old_r != 1 {
+        return None;
+    }
+    Some(old_s.rem_euclid(modulus as i128) as u64)
+}
+
+/// Smallest `x` with `base^x = target mod modulus`, using baby-step giant-step.
+///
+/// `base` does not need to be coprime with `modulus`, the common factors are divided
+/// out first. `None` for moduli below 2.
+pub fn discrete_log(base: u64, target: u64, modulus: u64) -> Option<u64> {
+    if modulus < 2 {
+        return None;
+    }
+    let (base, mut target, mut modulus) = (base % modulus, target % modulus, modulus);
+    // Solves `coefficient * base^y = target` from here on, with `x = y + offset`.
+    let (mut coefficient, mut offset) = (1 % modulus, 0);
+    loop {
+        let g = gcd(base, modulus);
+        if g == 1 {
+            break;
+        }
+        if target == coefficient {
+            return Some(offset);
+        }
+        if target % g != 0 {
+            return None;
+        }
+        target /= g;
+        modulus /= g;
+        offset += 1;
+        coefficient = mul_mod(coefficient, base / g, modulus);
+    }
+    let target = mul_mod(target, mod_inverse(coefficient, modulus)?, modulus);
+    let steps = (modulus as f64).sqrt().ceil() as u64;
+    // Baby steps: base^j for every j below `steps`, keeping the smallest j.
+    let mut baby = HashMap::new();
+    let mut value = 1 % modulus;
+    for j in 0..steps {
+        baby.entry(value).or_insert(j);
+        value = mul_mod(value, base, modulus);
+    }
+    // Giant steps: target * base^(-i * steps) until it lands on a baby step.
+    let factor = mod_inverse(mod_pow(base, steps, modulus), modulus)?;
+    let mut gamma = target;
+    for i in 0..steps {
+        if let Some(j) = baby.get(&gamma) {
+            return Some(i * steps + j + offset);
+        }
+        gamma = mul_mod(gamma, factor, modulus);
+    }
+    None
+}
+
+#[test]
+fn test_gcd() {
+    assert_eq!(6, gcd(12, 18));
+    assert_eq!(1, gcd(17, 5));
+    assert_eq!(7, gcd(0, 7));
+    assert_eq!(0, gcd(0, 0));
+}
+
+#[test]
+fn test_mod_pow() {
+    assert_eq!(5764801, mod_pow(7, 8, 20201227));
+    assert_eq!(17807724, mod_pow(7, 11, 20201227));
+    assert_eq!(1, mod_pow(3, 0, 7));
+    assert_eq!(0, mod_pow(3, 5, 1));
+    assert_eq!(1_000_000_000_000_000_000, mod_pow(10, 18, u64::MAX));
+}
+
+#[test]
+fn test_mod_inverse() {
+    assert_eq!(Some(4), mod_inverse(3, 11));
+    assert_eq!(Some(1), mod_inverse(1, 20201227));
+    assert_eq!(None, mod_inverse(4, 8));
+    assert_eq!(None, mod_inverse(3, 0));
+    let inverse = mod_inverse(7, 20201227).unwrap();
+    assert_eq!(1, mul_mod(7, inverse, 20201227));
+}
+
+#[test]
+fn test_discrete_log() {
+    assert_eq!(Some(8), discrete_log(7, 5764801, 20201227));
+    assert_eq!(Some(11), discrete_log(7, 17807724, 20201227));
+    assert_eq!(Some(0), discrete_log(7, 1, 20201227));
+    assert_eq!(Some(2), discrete_log(2, 4, 7));
+    // 2 only generates {1, 2, 4} modulo 7
+    assert_eq!(None, discrete_log(2, 3, 7));
+    assert_eq!(Some(2), discrete_log(2, 4, 8));
+    assert_eq!(Some(3), discrete_log(2, 0, 8));
+    assert_eq!(Some(0), discrete_log(2, 1, 8));
+    assert_eq!(None, discrete_log(2, 3, 8));
+    assert_eq!(Some(2), discrete_log(6, 0, 9));
+    assert_eq!(None, discrete_log(6, 3, 9));
+    assert_eq!(None, discrete_log(7, 1, 0));
+    assert_eq!(None, discrete_log(7, 1, 1));
+    // Brute force agrees on every base and target of small moduli
+    for modulus in 2..40 {
+        for base in 0..modulus {
+            for target in 0..modulus {
+                let expected = (0..2 * modulus).find(|x| mod_pow(base, *x, modulus) == target);
+                assert_eq!(expected, discrete_log(base, target, modulus));
+            }
+        }
+    }
+    let x = 1_234_567;
+    assert_eq!(Some(x), discrete_log(7, mod_pow(7, x, 20201227), 20201227));
+}
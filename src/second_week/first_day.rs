@@ -38,7 +38,7 @@ impl Instruction {
     fn new(inp: String) -> Self {
         let mut split = inp.split_whitespace();
         let ins = split.next().unwrap();
-        let mov: i16 = split.next().map(|s| s.parse().unwrap_or(0 as i16)).unwrap();
+        let mov: i16 = split.next().map(|s| s.parse().unwrap_or(0_i16)).unwrap();
         if ins == "acc" {
             return Self::Acc(mov);
         } else if ins == "jmp" {
@@ -44,7 +44,7 @@ pub fn find_number(input: Vec<String>, pmb_num: usize) -> Option<i32> {
     let (preamble, _) = create_preamble(input, pmb_num);
     preamble
         .into_iter()
-        .find(|(n, p)| !find_in_preamble(n, &p))
+        .find(|(n, p)| !find_in_preamble(n, p))
         .map(|(n, _)| n)
 }
 
@@ -52,7 +52,7 @@ pub fn find_number_in_range(input: Vec<String>, pmb_num: usize) -> Option<i32> {
     let (preamble, list) = create_preamble(input, pmb_num);
     if let Some(n) = preamble
         .into_iter()
-        .find(|(n, p)| !find_in_preamble(n, &p))
+        .find(|(n, p)| !find_in_preamble(n, p))
         .map(|(n, _)| n)
     {
         return find_range(n, list);
@@ -7,12 +7,7 @@ fn find_differences(input: Vec<String>) -> (u64, u64, u64) {
         .iter()
         .enumerate()
         .fold((0, 0, 1), |(mut acc1, mut acc2, mut acc3), (i, v)| {
-            let diff;
-            if i == 0 {
-                diff = *v;
-            } else {
-                diff = v - values[i - 1];
-            }
+            let diff = if i == 0 { *v } else { v - values[i - 1] };
             match diff {
                 1 => acc1 += 1,
                 2 => acc2 += 1,
@@ -0,0 +1,86 @@
+mod fifth_day;
+mod fourth_day;
+mod second_day;
+mod seventh_day;
+mod sixth_day;
+mod third_day;
+
+use fifth_day::*;
+use fourth_day::*;
+use second_day::*;
+use seventh_day::*;
+use sixth_day::*;
+use third_day::*;
+
+use super::lines_from_file;
+
+pub fn run_third_week() {
+    run_second_day();
+    run_third_day();
+    run_fourth_day();
+    run_fifth_day();
+    run_sixth_day();
+    run_seventh_day();
+}
+
+fn run_second_day() {
+    let input = lines_from_file("inputs/third_week/day2.txt").expect("Could not load lines");
+    let result = scanning_error_rate(input.clone());
+    println!("The ticket scanning error rate is {}", result);
+    match departure_product(input, "departure") {
+        Ok(n) => println!("The departure fields product is {}", n),
+        Err(e) => println!("Could not deduce the ticket fields: {:?}", e),
+    }
+}
+
+fn run_third_day() {
+    let input = lines_from_file("inputs/third_week/day3.txt").expect("Could not load lines");
+    let result = active_cubes::<3>(input.clone(), 6);
+    println!("Active cubes in 3 dimensions: {}", result);
+    let result = active_cubes::<4>(input, 6);
+    println!("Active cubes in 4 dimensions: {}", result);
+}
+
+fn run_fourth_day() {
+    let input = lines_from_file("inputs/third_week/day4.txt").expect("Could not load lines");
+    match sum_expressions(input.clone(), Precedence::EQUAL) {
+        Ok(n) => println!("The sum of the expressions is {}", n),
+        Err(e) => println!("Could not parse the homework: {:?}", e),
+    }
+    match sum_expressions(input, Precedence::ADDITION_FIRST) {
+        Ok(n) => println!("The sum of the advanced expressions is {}", n),
+        Err(e) => println!("Could not parse the homework: {:?}", e),
+    }
+}
+
+fn run_fifth_day() {
+    let input = lines_from_file("inputs/third_week/day5.txt").expect("Could not load lines");
+    match count_matches(input.clone(), &[]) {
+        Ok(n) => println!("Messages matching rule 0: {}", n),
+        Err(e) => println!("Could not read the rules: {:?}", e),
+    }
+    match count_matches(input, &["8: 42 | 42 8", "11: 42 31 | 42 11 31"]) {
+        Ok(n) => println!("Messages matching the looping rule 0: {}", n),
+        Err(e) => println!("Could not read the looping rules: {:?}", e),
+    }
+}
+
+fn run_sixth_day() {
+    let input = lines_from_file("inputs/third_week/day6.txt").expect("Could not load lines");
+    let result = corners_product(input.clone());
+    println!("The product of the corner tiles is {}", result);
+    match water_roughness(input, &SEA_MONSTER) {
+        Some(n) => println!("The water roughness is {}", n),
+        None => println!("Could not assemble the image"),
+    }
+}
+
+fn run_seventh_day() {
+    let input = lines_from_file("inputs/third_week/day7.txt").expect("Could not load lines");
+    let result = safe_ingredients(input.clone());
+    println!("Safe ingredients appear {} times", result);
+    match dangerous_ingredients(input) {
+        Ok(list) => println!("The canonical dangerous ingredient list is {}", list),
+        Err(e) => println!("Could not resolve the allergens: {:?}", e),
+    }
+}
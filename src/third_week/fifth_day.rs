@@ -0,0 +1,375 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+#[derive(Debug, Eq, PartialEq)]
+enum Rule {
+    Char(char),
+    Alternatives(Vec<Vec<usize>>),
+}
+
+impl Rule {
+    fn new(inp: &str) -> Option<Self> {
+        let inp = inp.trim();
+        if let Some(quoted) = inp.strip_prefix('"') {
+            // Exactly one character between a pair of quotes
+            let mut chars = quoted.strip_suffix('"')?.chars();
+            return match (chars.next(), chars.next()) {
+                (Some(c), None) => Some(Self::Char(c)),
+                _ => None,
+            };
+        }
+        inp.split('|')
+            .map(|seq| seq.split_whitespace().map(|n| n.parse().ok()).collect())
+            .collect::<Option<_>>()
+            .map(Self::Alternatives)
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum GrammarError {
+    /// A rule line whose id or body could not be read.
+    BadRule(String),
+    /// A rule that can reach itself again without consuming any input.
+    LeftRecursive(usize),
+}
+
+/// How a message was derived from a rule, one node per rule applied.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Derivation {
+    Leaf(usize, char),
+    Node(usize, Vec<Derivation>),
+}
+
+impl Derivation {
+    fn write(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        match self {
+            Derivation::Leaf(rule, c) => writeln!(f, "{}{}: \"{}\"", "  ".repeat(depth), rule, c),
+            Derivation::Node(rule, children) => {
+                writeln!(f, "{}{}", "  ".repeat(depth), rule)?;
+                children.iter().try_for_each(|c| c.write(f, depth + 1))
+            }
+        }
+    }
+}
+
+impl fmt::Display for Derivation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write(f, 0)
+    }
+}
+
+struct Grammar {
+    rules: HashMap<usize, Rule>,
+}
+
+impl Grammar {
+    fn new(input: &[String]) -> Result<Self, GrammarError> {
+        let mut grammar = Self {
+            rules: HashMap::new(),
+        };
+        input.iter().try_for_each(|s| grammar.replace(s))?;
+        Ok(grammar)
+    }
+
+    /// Adds a `<id>: <rule>` line, replacing any rule with the same id.
+    fn replace(&mut self, inp: &str) -> Result<(), GrammarError> {
+        let mut split = inp.splitn(2, ':');
+        let id = split.next().and_then(|id| id.trim().parse().ok());
+        let rule = split.next().and_then(Rule::new);
+        match (id, rule) {
+            (Some(id), Some(rule)) => {
+                self.rules.insert(id, rule);
+                Ok(())
+            }
+            _ => Err(GrammarError::BadRule(inp.to_string())),
+        }
+    }
+
+    // Rules that can match without consuming anything, found by a fixed point.
+    fn nullable(&self) -> HashSet<usize> {
+        let mut nullable = HashSet::new();
+        loop {
+            let found: Vec<usize> = self
+                .rules
+                .iter()
+                .filter(|(id, _)| !nullable.contains(*id))
+                .filter(|(_, rule)| match rule {
+                    Rule::Char(_) => false,
+                    Rule::Alternatives(alternatives) => alternatives
+                        .iter()
+                        .any(|seq| seq.iter().all(|r| nullable.contains(r))),
+                })
+                .map(|(id, _)| *id)
+                .collect();
+            if found.is_empty() {
+                return nullable;
+            }
+            nullable.extend(found);
+        }
+    }
+
+    /// Rejects grammars where `ends` would recurse forever: a rule reaching itself
+    /// through the first rules of its sequences, before consuming any input.
+    fn check_left_recursion(&self) -> Result<(), GrammarError> {
+        let nullable = self.nullable();
+        let leftmost = |id: &usize| -> Vec<usize> {
+            match self.rules.get(id) {
+                Some(Rule::Alternatives(alternatives)) => alternatives
+                    .iter()
+                    .flat_map(|seq| {
+                        let prefix = seq.iter().take_while(|r| nullable.contains(r)).count();
+                        seq.iter().take(prefix + 1).copied()
+                    })
+                    .collect(),
+                _ => Vec::new(),
+            }
+        };
+        let mut done = HashSet::new();
+        let mut ids: Vec<usize> = self.rules.keys().copied().collect();
+        ids.sort_unstable();
+        for id in ids {
+            // Depth first, `path` holds the rules on the current chain.
+            let mut path = vec![id];
+            let mut pending = vec![leftmost(&id)];
+            while let Some(next) = pending.last_mut() {
+                match next.pop() {
+                    Some(r) if path.contains(&r) => return Err(GrammarError::LeftRecursive(r)),
+                    Some(r) if !done.contains(&r) => {
+                        pending.push(leftmost(&r));
+                        path.push(r);
+                    }
+                    Some(_) => {}
+                    None => {
+                        pending.pop();
+                        done.extend(path.pop());
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Every position where `rule` can stop matching when started at `start`.
+    // Recursive rules are fine as long as they consume input before recursing,
+    // which `check_left_recursion` makes sure of.
+    fn ends(&self, rule: usize, message: &[char], start: usize) -> Vec<usize> {
+        match self.rules.get(&rule) {
+            Some(Rule::Char(c)) => {
+                if message.get(start) == Some(c) {
+                    vec![start + 1]
+                } else {
+                    Vec::new()
+                }
+            }
+            Some(Rule::Alternatives(alternatives)) => {
+                let mut ends: Vec<usize> = alternatives
+                    .iter()
+                    .flat_map(|seq| {
+                        seq.iter().fold(vec![start], |starts, r| {
+                            starts
+                                .into_iter()
+                                .flat_map(|s| self.ends(*r, message, s))
+                                .collect()
+                        })
+                    })
+                    .collect();
+                ends.sort_unstable();
+                ends.dedup();
+                ends
+            }
+            None => Vec::new(),
+        }
+    }
+
+    fn derivations(&self, rule: usize, message: &[char], start: usize) -> Vec<(usize, Derivation)> {
+        match self.rules.get(&rule) {
+            Some(Rule::Char(c)) => {
+                if message.get(start) == Some(c) {
+                    vec![(start + 1, Derivation::Leaf(rule, *c))]
+                } else {
+                    Vec::new()
+                }
+            }
+            Some(Rule::Alternatives(alternatives)) => alternatives
+                .iter()
+                .flat_map(|seq| {
+                    seq.iter()
+                        .fold(vec![(start, Vec::new())], |partials, r| {
+                            partials
+                                .into_iter()
+                                .flat_map(|(s, children): (usize, Vec<Derivation>)| {
+                                    self.derivations(*r, message, s).into_iter().map(
+                                        move |(end, d)| {
+                                            let mut children = children.clone();
+                                            children.push(d);
+                                            (end, children)
+                                        },
+                                    )
+                                })
+                                .collect()
+                        })
+                        .into_iter()
+                        .map(|(end, children)| (end, Derivation::Node(rule, children)))
+                })
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    fn matches(&self, message: &str) -> bool {
+        let message: Vec<char> = message.chars().collect();
+        self.ends(0, &message, 0).contains(&message.len())
+    }
+
+    fn derive(&self, message: &str) -> Option<Derivation> {
+        let message: Vec<char> = message.chars().collect();
+        self.derivations(0, &message, 0)
+            .into_iter()
+            .find(|(end, _)| *end == message.len())
+            .map(|(_, d)| d)
+    }
+}
+
+fn create_grammar(
+    input: &[String],
+    replacements: &[&str],
+) -> Result<(Grammar, Vec<String>), GrammarError> {
+    let mut sections = input.split(|s| s.is_empty());
+    let mut grammar = Grammar::new(sections.next().unwrap_or(&[]))?;
+    replacements.iter().try_for_each(|r| grammar.replace(r))?;
+    grammar.check_left_recursion()?;
+    Ok((grammar, sections.next().unwrap_or(&[]).to_vec()))
+}
+
+pub fn count_matches(input: Vec<String>, replacements: &[&str]) -> Result<usize, GrammarError> {
+    let (grammar, messages) = create_grammar(&input, replacements)?;
+    Ok(messages.iter().filter(|m| grammar.matches(m)).count())
+}
+
+/// Derivation tree of `message` from rule 0, if the message matches.
+#[allow(dead_code)]
+pub fn derivation(
+    input: Vec<String>,
+    replacements: &[&str],
+    message: &str,
+) -> Result<Option<Derivation>, GrammarError> {
+    let (grammar, _) = create_grammar(&input, replacements)?;
+    Ok(grammar.derive(message))
+}
+
+#[test]
+fn test_new_rule() {
+    assert_eq!(Some(Rule::Char('a')), Rule::new(" \"a\""));
+    assert_eq!(
+        Some(Rule::Alternatives(vec![vec![2, 3], vec![3, 2]])),
+        Rule::new(" 2 3 | 3 2")
+    );
+    assert_eq!(
+        Some(Rule::Alternatives(vec![vec![4, 1, 5]])),
+        Rule::new("4 1 5")
+    );
+    assert_eq!(None, Rule::new("4 x"));
+    assert_eq!(None, Rule::new("\"\""));
+    assert_eq!(None, Rule::new("\"ab\""));
+    assert_eq!(None, Rule::new("\"a"));
+    assert_eq!(None, Rule::new("\""));
+}
+
+#[test]
+fn test_count_matches() {
+    let input = vec![
+        "0: 4 1 5".to_string(),
+        "1: 2 3 | 3 2".to_string(),
+        "2: 4 4 | 5 5".to_string(),
+        "3: 4 5 | 5 4".to_string(),
+        "4: \"a\"".to_string(),
+        "5: \"b\"".to_string(),
+        "".to_string(),
+        "ababbb".to_string(),
+        "bababa".to_string(),
+        "abbbab".to_string(),
+        "aaabbb".to_string(),
+        "aaaabbb".to_string(),
+    ];
+    assert_eq!(Ok(2), count_matches(input, &[]))
+}
+
+#[test]
+fn test_count_recursive_matches() {
+    let input = vec![
+        "0: 8 11".to_string(),
+        "8: 42".to_string(),
+        "11: 42 31".to_string(),
+        "42: \"a\"".to_string(),
+        "31: \"b\"".to_string(),
+        "".to_string(),
+        "aab".to_string(),
+        "aaab".to_string(),
+        "aabb".to_string(),
+        "aaabb".to_string(),
+        "abb".to_string(),
+    ];
+    assert_eq!(Ok(1), count_matches(input.clone(), &[]));
+    let replacements = ["8: 42 | 42 8", "11: 42 31 | 42 11 31"];
+    assert_eq!(Ok(3), count_matches(input, &replacements));
+}
+
+#[test]
+fn test_derivation() {
+    let input = vec![
+        "0: 8 11".to_string(),
+        "8: 42".to_string(),
+        "11: 42 31".to_string(),
+        "42: \"a\"".to_string(),
+        "31: \"b\"".to_string(),
+    ];
+    let replacements = ["8: 42 | 42 8", "11: 42 31 | 42 11 31"];
+    assert_eq!(Ok(None), derivation(input.clone(), &replacements, "abb"));
+    let tree = derivation(input, &replacements, "aaabb").unwrap().unwrap();
+    let expected = vec![
+        "0",
+        "  8",
+        "    42: \"a\"",
+        "  11",
+        "    42: \"a\"",
+        "    11",
+        "      42: \"a\"",
+        "      31: \"b\"",
+        "    31: \"b\"",
+        "",
+    ];
+    assert_eq!(expected.join("\n"), tree.to_string());
+}
+
+#[test]
+fn test_grammar_errors() {
+    let input = vec![
+        "0: 1 2".to_string(),
+        "1: \"a\"".to_string(),
+        "2: \"b\"".to_string(),
+        "".to_string(),
+        "ab".to_string(),
+    ];
+    assert_eq!(Ok(1), count_matches(input.clone(), &[]));
+    assert_eq!(
+        Err(GrammarError::BadRule("x: 1".to_string())),
+        count_matches(input.clone(), &["x: 1"])
+    );
+    assert_eq!(
+        Err(GrammarError::BadRule("3 \"c\"".to_string())),
+        count_matches(input.clone(), &["3 \"c\""])
+    );
+    assert_eq!(
+        Err(GrammarError::LeftRecursive(1)),
+        count_matches(input.clone(), &["1: 1 2 | 2"])
+    );
+    assert_eq!(
+        Err(GrammarError::LeftRecursive(1)),
+        count_matches(input.clone(), &["1: 3 2", "3: 1"])
+    );
+    assert_eq!(
+        Err(GrammarError::LeftRecursive(1)),
+        count_matches(input.clone(), &["1: 3 1", "3: "])
+    );
+    assert_eq!(Ok(1), count_matches(input, &["0: 1 0 | 1 2"]));
+}
@@ -0,0 +1,323 @@
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Operator {
+    Add,
+    Mul,
+}
+
+impl Operator {
+    fn apply(&self, left: u64, right: u64) -> Option<u64> {
+        match self {
+            Operator::Add => left.checked_add(right),
+            Operator::Mul => left.checked_mul(right),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum Token {
+    Number(u64),
+    Op(Operator),
+    Open,
+    Close,
+}
+
+/// Binding power of every operator, higher binds tighter.
+#[derive(Debug, Clone, Copy)]
+pub struct Precedence {
+    pub add: u8,
+    pub mul: u8,
+}
+
+impl Precedence {
+    pub const EQUAL: Self = Self { add: 1, mul: 1 };
+    pub const ADDITION_FIRST: Self = Self { add: 2, mul: 1 };
+
+    fn of(&self, op: Operator) -> u8 {
+        match op {
+            Operator::Add => self.add,
+            Operator::Mul => self.mul,
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+enum Expression {
+    Number(u64),
+    Binary(Operator, Box<Expression>, Box<Expression>),
+}
+
+impl Expression {
+    /// `None` when some intermediate result does not fit in a `u64`.
+    fn evaluate(&self) -> Option<u64> {
+        match self {
+            Expression::Number(n) => Some(*n),
+            Expression::Binary(op, left, right) => op.apply(left.evaluate()?, right.evaluate()?),
+        }
+    }
+}
+
+// Prints the expression fully parenthesized, so the applied precedence is visible.
+impl fmt::Display for Expression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expression::Number(n) => write!(f, "{}", n),
+            Expression::Binary(op, left, right) => {
+                let symbol = match op {
+                    Operator::Add => '+',
+                    Operator::Mul => '*',
+                };
+                write!(f, "({} {} {})", left, symbol, right)
+            }
+        }
+    }
+}
+
+/// Errors carry the 1-based column where the expression went wrong.
+#[derive(Debug, Eq, PartialEq)]
+pub enum ParseError {
+    UnexpectedChar(usize, char),
+    UnexpectedToken(usize),
+    UnclosedParen(usize),
+    UnexpectedEnd,
+    NumberTooLarge(usize),
+    /// The expression parses, but its value does not fit in a `u64`.
+    Overflow,
+}
+
+fn tokenize(inp: &str) -> Result<Vec<(usize, Token)>, ParseError> {
+    let mut tokens = Vec::new();
+    let mut chars = inp.chars().enumerate().peekable();
+    while let Some((i, c)) = chars.next() {
+        let token = match c {
+            ' ' => continue,
+            '+' => Token::Op(Operator::Add),
+            '*' => Token::Op(Operator::Mul),
+            '(' => Token::Open,
+            ')' => Token::Close,
+            '0'..='9' => {
+                let mut n = c.to_digit(10).unwrap() as u64;
+                while let Some(d) = chars.peek().and_then(|(_, c)| c.to_digit(10)) {
+                    n = n
+                        .checked_mul(10)
+                        .and_then(|n| n.checked_add(d as u64))
+                        .ok_or(ParseError::NumberTooLarge(i + 1))?;
+                    chars.next();
+                }
+                Token::Number(n)
+            }
+            _ => return Err(ParseError::UnexpectedChar(i + 1, c)),
+        };
+        tokens.push((i + 1, token));
+    }
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    tokens: &'a [(usize, Token)],
+    index: usize,
+    precedence: Precedence,
+}
+
+impl<'a> Parser<'a> {
+    fn new(tokens: &'a [(usize, Token)], precedence: Precedence) -> Self {
+        Self {
+            tokens,
+            index: 0,
+            precedence,
+        }
+    }
+
+    fn next(&mut self) -> Option<(usize, Token)> {
+        let token = self.tokens.get(self.index).cloned();
+        self.index += 1;
+        token
+    }
+
+    fn primary(&mut self) -> Result<Expression, ParseError> {
+        match self.next() {
+            Some((_, Token::Number(n))) => Ok(Expression::Number(n)),
+            Some((column, Token::Open)) => {
+                let inner = self.expression(0)?;
+                match self.next() {
+                    Some((_, Token::Close)) => Ok(inner),
+                    Some((c, _)) => Err(ParseError::UnexpectedToken(c)),
+                    None => Err(ParseError::UnclosedParen(column)),
+                }
+            }
+            Some((column, _)) => Err(ParseError::UnexpectedToken(column)),
+            None => Err(ParseError::UnexpectedEnd),
+        }
+    }
+
+    // Precedence climbing, every operator is left associative.
+    // Works in u16, so one level above the highest `u8` precedence still exists.
+    fn expression(&mut self, min_precedence: u16) -> Result<Expression, ParseError> {
+        let mut left = self.primary()?;
+        while let Some((_, Token::Op(op))) = self.tokens.get(self.index).cloned() {
+            let precedence = u16::from(self.precedence.of(op));
+            if precedence < min_precedence {
+                break;
+            }
+            self.index += 1;
+            let right = self.expression(precedence + 1)?;
+            left = Expression::Binary(op, Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+}
+
+fn parse(inp: &str, precedence: Precedence) -> Result<Expression, ParseError> {
+    let tokens = tokenize(inp)?;
+    let mut parser = Parser::new(&tokens, precedence);
+    let expression = parser.expression(0)?;
+    match parser.next() {
+        Some((column, _)) => Err(ParseError::UnexpectedToken(column)),
+        None => Ok(expression),
+    }
+}
+
+/// Error of one line of the homework, `line` counts from 1.
+#[derive(Debug, Eq, PartialEq)]
+pub struct HomeworkError {
+    line: usize,
+    error: ParseError,
+}
+
+pub fn sum_expressions(input: Vec<String>, precedence: Precedence) -> Result<u64, HomeworkError> {
+    input.iter().enumerate().try_fold(0_u64, |acc, (i, s)| {
+        parse(s, precedence)
+            .and_then(|e| {
+                e.evaluate()
+                    .and_then(|value| acc.checked_add(value))
+                    .ok_or(ParseError::Overflow)
+            })
+            .map_err(|error| HomeworkError { line: i + 1, error })
+    })
+}
+
+#[test]
+fn test_tokenize() {
+    assert_eq!(
+        Ok(vec![
+            (1, Token::Number(12)),
+            (4, Token::Op(Operator::Add)),
+            (6, Token::Open),
+            (7, Token::Number(3)),
+            (9, Token::Op(Operator::Mul)),
+            (11, Token::Number(4)),
+            (12, Token::Close),
+        ]),
+        tokenize("12 + (3 * 4)")
+    );
+    assert_eq!(Err(ParseError::UnexpectedChar(3, '-')), tokenize("1 - 2"));
+    assert_eq!(
+        Err(ParseError::NumberTooLarge(5)),
+        tokenize("1 + 12345678901234567890123")
+    );
+}
+
+#[test]
+fn test_parse() {
+    let expression = parse("1 + 2 * 3 + 4", Precedence::EQUAL).unwrap();
+    assert_eq!("(((1 + 2) * 3) + 4)", expression.to_string());
+    let expression = parse("1 + 2 * 3 + 4", Precedence::ADDITION_FIRST).unwrap();
+    assert_eq!("((1 + 2) * (3 + 4))", expression.to_string());
+    let expression = parse("2 * (3 + 4)", Precedence::EQUAL).unwrap();
+    assert_eq!("(2 * (3 + 4))", expression.to_string());
+    assert_eq!(
+        Err(ParseError::UnclosedParen(5)),
+        parse("2 * (3 + 4", Precedence::EQUAL)
+    );
+    assert_eq!(
+        Err(ParseError::UnexpectedToken(5)),
+        parse("2 * * 4", Precedence::EQUAL)
+    );
+    assert_eq!(
+        Err(ParseError::UnexpectedToken(3)),
+        parse("2 3", Precedence::EQUAL)
+    );
+    assert_eq!(
+        Err(ParseError::UnexpectedToken(5)),
+        parse("2 + )", Precedence::EQUAL)
+    );
+    assert_eq!(
+        Err(ParseError::UnexpectedEnd),
+        parse("2 +", Precedence::EQUAL)
+    );
+}
+
+#[test]
+fn test_evaluate() {
+    let cases = vec![
+        ("1 + (2 * 3) + (4 * (5 + 6))", 51, 51),
+        ("2 * 3 + (4 * 5)", 26, 46),
+        ("5 + (8 * 3 + 9 + 3 * 4 * 3)", 437, 1445),
+        ("5 * 9 * (7 * 3 * 3 + 9 * 3 + (8 + 6 * 4))", 12240, 669060),
+        (
+            "((2 + 4 * 9) * (6 + 9 * 8 + 6) + 6) + 2 + 4 * 2",
+            13632,
+            23340,
+        ),
+    ];
+    for (inp, equal, addition_first) in cases {
+        assert_eq!(
+            Some(equal),
+            parse(inp, Precedence::EQUAL).unwrap().evaluate()
+        );
+        assert_eq!(
+            Some(addition_first),
+            parse(inp, Precedence::ADDITION_FIRST).unwrap().evaluate()
+        );
+    }
+}
+
+#[test]
+fn test_sum_expressions() {
+    let input = vec![
+        "1 + 2 * 3 + 4 * 5 + 6".to_string(),
+        "2 * 3 + (4 * 5)".to_string(),
+    ];
+    assert_eq!(
+        Ok(71 + 26),
+        sum_expressions(input.clone(), Precedence::EQUAL)
+    );
+    assert_eq!(
+        Ok(231 + 46),
+        sum_expressions(input, Precedence::ADDITION_FIRST)
+    );
+    let input = vec!["1 + 2".to_string(), "(1 + 2".to_string()];
+    assert_eq!(
+        Err(HomeworkError {
+            line: 2,
+            error: ParseError::UnclosedParen(1)
+        }),
+        sum_expressions(input, Precedence::EQUAL)
+    );
+}
+
+#[test]
+fn test_overflow() {
+    let expression = parse("99999999999 * 99999999999", Precedence::EQUAL).unwrap();
+    assert_eq!(None, expression.evaluate());
+    let input = vec!["99999999999 * 99999999999".to_string()];
+    assert_eq!(
+        Err(HomeworkError {
+            line: 1,
+            error: ParseError::Overflow
+        }),
+        sum_expressions(input, Precedence::EQUAL)
+    );
+    let input = vec![u64::MAX.to_string(), "1".to_string()];
+    assert_eq!(
+        Err(HomeworkError {
+            line: 2,
+            error: ParseError::Overflow
+        }),
+        sum_expressions(input, Precedence::EQUAL)
+    );
+    let highest = Precedence { add: 255, mul: 255 };
+    let expression = parse("1 + 2 * 3 + 4", highest).unwrap();
+    assert_eq!("(((1 + 2) * 3) + 4)", expression.to_string());
+}
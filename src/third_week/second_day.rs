@@ -0,0 +1,381 @@
+use std::collections::HashSet;
+
+#[derive(Debug, Eq, PartialEq)]
+struct Rule {
+    name: String,
+    ranges: Vec<(u32, u32)>,
+}
+
+impl Rule {
+    fn new(inp: &str) -> Self {
+        let mut split = inp.split(": ");
+        let name = split.next().unwrap().to_string();
+        let ranges = split
+            .next()
+            .unwrap()
+            .split(" or ")
+            .map(|r| {
+                let mut bounds = r.split('-').map(|n| n.trim().parse().unwrap_or(0));
+                (bounds.next().unwrap(), bounds.next().unwrap())
+            })
+            .collect();
+        Self { name, ranges }
+    }
+
+    fn contains(&self, value: u32) -> bool {
+        self.ranges
+            .iter()
+            .any(|(min, max)| value >= *min && value <= *max)
+    }
+}
+
+#[derive(Debug)]
+struct Notes {
+    rules: Vec<Rule>,
+    ticket: Vec<u32>,
+    nearby: Vec<Vec<u32>>,
+}
+
+impl Notes {
+    fn valid_tickets(&self) -> Vec<&Vec<u32>> {
+        self.nearby
+            .iter()
+            .filter(|t| t.iter().all(|v| self.rules.iter().any(|r| r.contains(*v))))
+            .collect()
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum AssignmentError {
+    /// Some position has no value left, or the values cannot all be placed.
+    Unsatisfiable,
+    /// More than one assignment fits; holds the positions that can be swapped around.
+    Ambiguous(Vec<usize>),
+    /// Your ticket is missing or does not hold one value per rule.
+    TicketLength { expected: usize, found: usize },
+}
+
+fn parse_ticket(inp: &str) -> Vec<u32> {
+    inp.split(',')
+        .map(|n| n.trim().parse().unwrap_or(0))
+        .collect()
+}
+
+fn create_notes(input: Vec<String>) -> Notes {
+    let mut sections = input.split(|s| s.is_empty());
+    let rules = sections
+        .next()
+        .unwrap_or(&[])
+        .iter()
+        .map(|s| Rule::new(s))
+        .collect();
+    let ticket = sections
+        .next()
+        .and_then(|s| s.get(1))
+        .map(|s| parse_ticket(s))
+        .unwrap_or_default();
+    let nearby = sections
+        .next()
+        .map(|s| s.iter().skip(1).map(|t| parse_ticket(t)).collect())
+        .unwrap_or_default();
+    Notes {
+        rules,
+        ticket,
+        nearby,
+    }
+}
+
+// Narrows the candidates by fixing every position with a single value left and
+// removing that value from the rest, until nothing changes.
+fn propagate(candidates: &mut [HashSet<usize>]) -> Result<(), AssignmentError> {
+    let mut fixed: HashSet<usize> = HashSet::new();
+    loop {
+        if candidates.iter().any(|c| c.is_empty()) {
+            return Err(AssignmentError::Unsatisfiable);
+        }
+        let single = candidates
+            .iter()
+            .filter(|c| c.len() == 1)
+            .map(|c| *c.iter().next().unwrap())
+            .find(|v| !fixed.contains(v));
+        match single {
+            Some(value) => {
+                fixed.insert(value);
+                candidates.iter_mut().filter(|c| c.len() > 1).for_each(|c| {
+                    c.remove(&value);
+                });
+            }
+            None => return Ok(()),
+        }
+    }
+}
+
+// Kuhn's augmenting path step, `owner` maps every value to the position holding it.
+fn augment(
+    position: usize,
+    candidates: &[HashSet<usize>],
+    owner: &mut [Option<usize>],
+    seen: &mut [bool],
+) -> bool {
+    for value in &candidates[position] {
+        if seen[*value] {
+            continue;
+        }
+        seen[*value] = true;
+        let free = match owner[*value] {
+            None => true,
+            Some(other) => augment(other, candidates, owner, seen),
+        };
+        if free {
+            owner[*value] = Some(position);
+            return true;
+        }
+    }
+    false
+}
+
+// A perfect matching is unique only if no position can move to an unused value
+// and no group of positions can rotate their values, which is a cycle in the
+// "position could take the value of" graph.
+fn find_cycle(
+    candidates: &[HashSet<usize>],
+    matched: &[usize],
+    values: usize,
+) -> Option<Vec<usize>> {
+    fn visit(
+        position: usize,
+        candidates: &[HashSet<usize>],
+        holder: &[usize],
+        state: &mut [u8],
+        path: &mut Vec<usize>,
+    ) -> Option<Vec<usize>> {
+        state[position] = 1;
+        path.push(position);
+        for value in &candidates[position] {
+            let next = holder[*value];
+            if next == usize::MAX {
+                return Some(vec![position]);
+            }
+            if next == position {
+                continue;
+            }
+            if state[next] == 1 {
+                let start = path.iter().position(|p| *p == next).unwrap();
+                return Some(path[start..].to_vec());
+            }
+            if state[next] == 0 {
+                if let Some(cycle) = visit(next, candidates, holder, state, path) {
+                    return Some(cycle);
+                }
+            }
+        }
+        path.pop();
+        state[position] = 2;
+        None
+    }
+
+    let mut holder = vec![usize::MAX; values];
+    matched
+        .iter()
+        .enumerate()
+        .for_each(|(position, value)| holder[*value] = position);
+    let mut state = vec![0; candidates.len()];
+    for position in 0..candidates.len() {
+        if state[position] == 0 {
+            if let Some(cycle) = visit(position, candidates, &holder, &mut state, &mut Vec::new()) {
+                return Some(cycle);
+            }
+        }
+    }
+    None
+}
+
+/// Assigns a distinct value to every position, given the values each position accepts.
+pub fn assign(candidates: Vec<HashSet<usize>>) -> Result<Vec<usize>, AssignmentError> {
+    let mut candidates = candidates;
+    propagate(&mut candidates)?;
+    let values = candidates
+        .iter()
+        .flat_map(|c| c.iter())
+        .max()
+        .map(|m| m + 1)
+        .unwrap_or(0);
+    let mut owner = vec![None; values];
+    for position in 0..candidates.len() {
+        if !augment(position, &candidates, &mut owner, &mut vec![false; values]) {
+            return Err(AssignmentError::Unsatisfiable);
+        }
+    }
+    let mut matched = vec![0; candidates.len()];
+    owner
+        .iter()
+        .enumerate()
+        .filter_map(|(value, position)| position.map(|p| (value, p)))
+        .for_each(|(value, position)| matched[position] = value);
+    if let Some(mut cycle) = find_cycle(&candidates, &matched, values) {
+        cycle.sort_unstable();
+        return Err(AssignmentError::Ambiguous(cycle));
+    }
+    Ok(matched)
+}
+
+fn field_positions(notes: &Notes) -> Result<Vec<usize>, AssignmentError> {
+    let tickets = notes.valid_tickets();
+    let candidates = (0..notes.rules.len())
+        .map(|position| {
+            notes
+                .rules
+                .iter()
+                .enumerate()
+                .filter(|(_, rule)| {
+                    tickets
+                        .iter()
+                        .all(|t| t.get(position).map(|v| rule.contains(*v)).unwrap_or(false))
+                })
+                .map(|(i, _)| i)
+                .collect()
+        })
+        .collect();
+    assign(candidates)
+}
+
+pub fn scanning_error_rate(input: Vec<String>) -> u32 {
+    let notes = create_notes(input);
+    notes
+        .nearby
+        .iter()
+        .flat_map(|t| t.iter())
+        .filter(|v| !notes.rules.iter().any(|r| r.contains(**v)))
+        .sum()
+}
+
+pub fn departure_product(input: Vec<String>, prefix: &str) -> Result<u64, AssignmentError> {
+    let notes = create_notes(input);
+    if notes.ticket.len() != notes.rules.len() {
+        return Err(AssignmentError::TicketLength {
+            expected: notes.rules.len(),
+            found: notes.ticket.len(),
+        });
+    }
+    Ok(field_positions(&notes)?
+        .into_iter()
+        .enumerate()
+        .filter(|(_, r)| notes.rules[*r].name.starts_with(prefix))
+        .map(|(i, _)| notes.ticket[i] as u64)
+        .product())
+}
+
+#[test]
+fn test_create_notes() {
+    let input = vec![
+        "class: 1-3 or 5-7".to_string(),
+        "row: 6-11 or 33-44".to_string(),
+        "seat: 13-40 or 45-50".to_string(),
+        "".to_string(),
+        "your ticket:".to_string(),
+        "7,1,14".to_string(),
+        "".to_string(),
+        "nearby tickets:".to_string(),
+        "7,3,47".to_string(),
+        "40,4,50".to_string(),
+        "55,2,20".to_string(),
+        "38,6,12".to_string(),
+    ];
+    let notes = create_notes(input);
+    assert_eq!(3, notes.rules.len());
+    assert_eq!(
+        Rule {
+            name: "row".to_string(),
+            ranges: vec![(6, 11), (33, 44)],
+        },
+        notes.rules[1]
+    );
+    assert_eq!(vec![7, 1, 14], notes.ticket);
+    assert_eq!(4, notes.nearby.len());
+    assert_eq!(vec![55, 2, 20], notes.nearby[2]);
+    assert_eq!(1, notes.valid_tickets().len());
+}
+
+#[test]
+fn test_scanning_error_rate() {
+    let input = vec![
+        "class: 1-3 or 5-7".to_string(),
+        "row: 6-11 or 33-44".to_string(),
+        "seat: 13-40 or 45-50".to_string(),
+        "".to_string(),
+        "your ticket:".to_string(),
+        "7,1,14".to_string(),
+        "".to_string(),
+        "nearby tickets:".to_string(),
+        "7,3,47".to_string(),
+        "40,4,50".to_string(),
+        "55,2,20".to_string(),
+        "38,6,12".to_string(),
+    ];
+    assert_eq!(71, scanning_error_rate(input))
+}
+
+#[test]
+fn test_field_positions() {
+    let input = vec![
+        "class: 0-1 or 4-19".to_string(),
+        "row: 0-5 or 8-19".to_string(),
+        "seat: 0-13 or 16-19".to_string(),
+        "".to_string(),
+        "your ticket:".to_string(),
+        "11,12,13".to_string(),
+        "".to_string(),
+        "nearby tickets:".to_string(),
+        "3,9,18".to_string(),
+        "15,1,5".to_string(),
+        "5,14,9".to_string(),
+    ];
+    assert_eq!(
+        Ok(vec![1, 0, 2]),
+        field_positions(&create_notes(input.clone()))
+    );
+    assert_eq!(Ok(13), departure_product(input.clone(), "se"));
+    assert_eq!(
+        Err(AssignmentError::TicketLength {
+            expected: 3,
+            found: 0
+        }),
+        departure_product(input[..3].to_vec(), "se")
+    );
+    let mut short = input;
+    short[5] = "11,12".to_string();
+    assert_eq!(
+        Err(AssignmentError::TicketLength {
+            expected: 3,
+            found: 2
+        }),
+        departure_product(short, "se")
+    );
+}
+
+#[test]
+fn test_assign() {
+    let sets = |v: Vec<Vec<usize>>| -> Vec<HashSet<usize>> {
+        v.into_iter().map(|s| s.into_iter().collect()).collect()
+    };
+    assert_eq!(
+        Ok(vec![1, 0, 2]),
+        assign(sets(vec![vec![0, 1, 2], vec![0], vec![0, 2]]))
+    );
+    assert_eq!(
+        Err(AssignmentError::Ambiguous(vec![0, 1])),
+        assign(sets(vec![vec![0, 1], vec![0, 1], vec![2]]))
+    );
+    assert_eq!(
+        Err(AssignmentError::Ambiguous(vec![1])),
+        assign(sets(vec![vec![0], vec![1, 2]]))
+    );
+    assert_eq!(
+        Err(AssignmentError::Unsatisfiable),
+        assign(sets(vec![vec![0], vec![0], vec![1, 2]]))
+    );
+    assert_eq!(
+        Err(AssignmentError::Unsatisfiable),
+        assign(sets(vec![vec![0, 1], vec![0, 1], vec![0, 1]]))
+    );
+}
@@ -0,0 +1,192 @@
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+use super::second_day::{assign, AssignmentError};
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum AllergenError {
+    /// Some allergen has no ingredient left, or they cannot all have their own.
+    Unsatisfiable,
+    /// More than one mapping fits; holds the allergens that can be swapped around.
+    Ambiguous(Vec<String>),
+}
+
+#[derive(Debug, Eq, PartialEq)]
+struct Food {
+    ingredients: HashSet<String>,
+    allergens: Vec<String>,
+}
+
+impl Food {
+    fn new(inp: &str) -> Self {
+        let mut split = inp.trim_end_matches(')').split(" (contains ");
+        let ingredients = split
+            .next()
+            .unwrap()
+            .split_whitespace()
+            .map(|s| s.to_string())
+            .collect();
+        let allergens = split
+            .next()
+            .map(|s| s.split(", ").map(|a| a.trim().to_string()).collect())
+            .unwrap_or_default();
+        Self {
+            ingredients,
+            allergens,
+        }
+    }
+}
+
+fn create_foods(input: Vec<String>) -> Vec<Food> {
+    input
+        .iter()
+        .filter(|s| !s.is_empty())
+        .map(|s| Food::new(s))
+        .collect()
+}
+
+// Ingredients that could hold each allergen, the ones present in every food listing it.
+fn candidates(foods: &[Food]) -> BTreeMap<&str, BTreeSet<&str>> {
+    let mut candidates: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+    for food in foods {
+        let ingredients: BTreeSet<&str> = food.ingredients.iter().map(|s| s.as_str()).collect();
+        for allergen in &food.allergens {
+            candidates
+                .entry(allergen)
+                .and_modify(|c| *c = c.intersection(&ingredients).cloned().collect())
+                .or_insert_with(|| ingredients.clone());
+        }
+    }
+    candidates
+}
+
+pub fn safe_ingredients(input: Vec<String>) -> usize {
+    let foods = create_foods(input);
+    let unsafe_ingredients: HashSet<&str> = candidates(&foods).into_values().flatten().collect();
+    foods
+        .iter()
+        .flat_map(|f| f.ingredients.iter())
+        .filter(|i| !unsafe_ingredients.contains(i.as_str()))
+        .count()
+}
+
+/// Ingredients holding an allergen, sorted by the allergen name and joined by commas.
+pub fn dangerous_ingredients(input: Vec<String>) -> Result<String, AllergenError> {
+    let foods = create_foods(input);
+    let candidates = candidates(&foods);
+    let ingredients: Vec<&str> = candidates
+        .values()
+        .flatten()
+        .cloned()
+        .collect::<BTreeSet<&str>>()
+        .into_iter()
+        .collect();
+    let positions = candidates
+        .values()
+        .map(|c| {
+            c.iter()
+                .map(|i| ingredients.binary_search(i).unwrap())
+                .collect()
+        })
+        .collect();
+    let assigned = assign(positions).map_err(|e| match e {
+        AssignmentError::Ambiguous(positions) => {
+            let allergens: Vec<&str> = candidates.keys().cloned().collect();
+            AllergenError::Ambiguous(
+                positions
+                    .iter()
+                    .map(|p| allergens[*p].to_string())
+                    .collect(),
+            )
+        }
+        AssignmentError::Unsatisfiable | AssignmentError::TicketLength { .. } => {
+            AllergenError::Unsatisfiable
+        }
+    })?;
+    Ok(assigned
+        .into_iter()
+        .map(|i| ingredients[i])
+        .collect::<Vec<&str>>()
+        .join(","))
+}
+
+#[test]
+fn test_new_food() {
+    let food = Food::new("mxmxvkd kfcds sqjhc nhms (contains dairy, fish)");
+    assert_eq!(4, food.ingredients.len());
+    assert!(food.ingredients.contains("sqjhc"));
+    assert_eq!(
+        vec!["dairy".to_string(), "fish".to_string()],
+        food.allergens
+    );
+    let food = Food::new("sqjhc fvjkl");
+    assert_eq!(2, food.ingredients.len());
+    assert!(food.allergens.is_empty());
+}
+
+#[test]
+fn test_candidates() {
+    let foods = create_foods(vec![
+        "mxmxvkd kfcds sqjhc nhms (contains dairy, fish)".to_string(),
+        "trh fvjkl sbzzf mxmxvkd (contains dairy)".to_string(),
+        "sqjhc fvjkl (contains soy)".to_string(),
+        "sqjhc mxmxvkd sbzzf (contains fish)".to_string(),
+    ]);
+    let candidates = candidates(&foods);
+    assert_eq!(3, candidates.len());
+    assert_eq!(
+        vec!["mxmxvkd"],
+        candidates["dairy"].iter().cloned().collect::<Vec<_>>()
+    );
+    assert_eq!(
+        vec!["mxmxvkd", "sqjhc"],
+        candidates["fish"].iter().cloned().collect::<Vec<_>>()
+    );
+    assert_eq!(
+        vec!["fvjkl", "sqjhc"],
+        candidates["soy"].iter().cloned().collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_safe_ingredients() {
+    let input = vec![
+        "mxmxvkd kfcds sqjhc nhms (contains dairy, fish)".to_string(),
+        "trh fvjkl sbzzf mxmxvkd (contains dairy)".to_string(),
+        "sqjhc fvjkl (contains soy)".to_string(),
+        "sqjhc mxmxvkd sbzzf (contains fish)".to_string(),
+    ];
+    assert_eq!(5, safe_ingredients(input))
+}
+
+#[test]
+fn test_dangerous_ingredients() {
+    let input = vec![
+        "mxmxvkd kfcds sqjhc nhms (contains dairy, fish)".to_string(),
+        "trh fvjkl sbzzf mxmxvkd (contains dairy)".to_string(),
+        "sqjhc fvjkl (contains soy)".to_string(),
+        "sqjhc mxmxvkd sbzzf (contains fish)".to_string(),
+    ];
+    assert_eq!(
+        Ok("mxmxvkd,sqjhc,fvjkl".to_string()),
+        dangerous_ingredients(input)
+    );
+    let input = vec![
+        "mxmxvkd sqjhc (contains dairy, fish)".to_string(),
+        "sqjhc mxmxvkd (contains fish)".to_string(),
+    ];
+    assert_eq!(
+        Err(AllergenError::Ambiguous(vec![
+            "dairy".to_string(),
+            "fish".to_string()
+        ])),
+        dangerous_ingredients(input)
+    );
+    let input = vec![
+        "mxmxvkd (contains dairy, fish)".to_string(),
+        "mxmxvkd sqjhc (contains fish)".to_string(),
+    ];
+    assert_eq!(
+        Err(AllergenError::Unsatisfiable),
+        dangerous_ingredients(input)
+    );
+}
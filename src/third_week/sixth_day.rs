@@ -0,0 +1,460 @@
+use std::collections::HashSet;
+
+type Grid = Vec<Vec<bool>>;
+
+pub const SEA_MONSTER: [&str; 3] = [
+    "                  # ",
+    "#    ##    ##    ###",
+    " #  #  #  #  #  #   ",
+];
+
+#[derive(Debug)]
+struct Tile {
+    id: u64,
+    grid: Grid,
+}
+
+impl Tile {
+    // Edges of the tile as it was given, in top, right, bottom, left order.
+    fn edges(&self) -> [u16; 4] {
+        edges(&self.grid)
+    }
+
+    // Every edge the tile can show once rotated or flipped.
+    fn signatures(&self) -> HashSet<u16> {
+        orientations(&self.grid)
+            .iter()
+            .flat_map(|g| edges(g).to_vec())
+            .collect()
+    }
+}
+
+fn rotate(grid: &[Vec<bool>]) -> Grid {
+    let size = grid.len();
+    (0..grid[0].len())
+        .map(|r| (0..size).map(|c| grid[size - 1 - c][r]).collect())
+        .collect()
+}
+
+fn flip(grid: &[Vec<bool>]) -> Grid {
+    grid.iter()
+        .map(|row| row.iter().rev().cloned().collect())
+        .collect()
+}
+
+// The four rotations of the grid followed by the four rotations of its mirror.
+fn orientations(grid: &[Vec<bool>]) -> Vec<Grid> {
+    let mut result = Vec::new();
+    for base in [grid.to_vec(), flip(grid)] {
+        let mut current = base;
+        for _ in 0..4 {
+            let next = rotate(&current);
+            result.push(current);
+            current = next;
+        }
+    }
+    result
+}
+
+fn to_bits<'a>(cells: impl Iterator<Item = &'a bool>) -> u16 {
+    cells.fold(0, |acc, c| (acc << 1) | *c as u16)
+}
+
+// Top and bottom read left to right, left and right read top to bottom.
+fn edges(grid: &[Vec<bool>]) -> [u16; 4] {
+    let last = grid[0].len() - 1;
+    [
+        to_bits(grid[0].iter()),
+        to_bits(grid.iter().map(|row| &row[last])),
+        to_bits(grid[grid.len() - 1].iter()),
+        to_bits(grid.iter().map(|row| &row[0])),
+    ]
+}
+
+fn parse_grid(input: &[String]) -> Grid {
+    input
+        .iter()
+        .map(|s| s.chars().map(|c| c == '#').collect())
+        .collect()
+}
+
+fn render(grid: &[Vec<bool>]) -> String {
+    grid.iter()
+        .map(|row| row.iter().map(|c| if *c { '#' } else { '.' }).collect())
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+fn create_tiles(input: Vec<String>) -> Vec<Tile> {
+    input
+        .split(|s| s.is_empty())
+        .filter(|t| !t.is_empty())
+        .map(|t| Tile {
+            id: t[0]
+                .trim_start_matches("Tile ")
+                .trim_end_matches(':')
+                .parse()
+                .unwrap_or(0),
+            grid: parse_grid(&t[1..]),
+        })
+        .collect()
+}
+
+// Corners are the tiles with exactly two edges that no other tile can show.
+fn find_corners(tiles: &[Tile]) -> Vec<u64> {
+    let signatures: Vec<HashSet<u16>> = tiles.iter().map(|t| t.signatures()).collect();
+    tiles
+        .iter()
+        .enumerate()
+        .filter(|(i, tile)| {
+            let unmatched = tile
+                .edges()
+                .iter()
+                .filter(|e| {
+                    !signatures
+                        .iter()
+                        .enumerate()
+                        .any(|(j, s)| j != *i && s.contains(e))
+                })
+                .count();
+            unmatched == 2
+        })
+        .map(|(_, tile)| tile.id)
+        .collect()
+}
+
+struct Assembly {
+    side: usize,
+    // Every orientation of every tile, together with its edges.
+    oriented: Vec<Vec<(Grid, [u16; 4])>>,
+    used: Vec<bool>,
+    placed: Vec<(usize, usize)>,
+}
+
+impl Assembly {
+    fn new(tiles: &[Tile]) -> Option<Self> {
+        let side = (tiles.len() as f64).sqrt() as usize;
+        if side == 0 || side * side != tiles.len() {
+            return None;
+        }
+        let oriented = tiles
+            .iter()
+            .map(|t| {
+                orientations(&t.grid)
+                    .into_iter()
+                    .map(|g| {
+                        let e = edges(&g);
+                        (g, e)
+                    })
+                    .collect()
+            })
+            .collect();
+        Some(Self {
+            side,
+            oriented,
+            used: vec![false; tiles.len()],
+            placed: Vec::new(),
+        })
+    }
+
+    fn edges_of(&self, position: usize) -> [u16; 4] {
+        let (tile, orientation) = self.placed[position];
+        self.oriented[tile][orientation].1
+    }
+
+    fn fits(&self, edges: &[u16; 4]) -> bool {
+        let position = self.placed.len();
+        let left = position.is_multiple_of(self.side) || self.edges_of(position - 1)[1] == edges[3];
+        let top = position < self.side || self.edges_of(position - self.side)[2] == edges[0];
+        left && top
+    }
+
+    // Fills the image row by row, undoing placements that lead to a dead end.
+    fn solve(&mut self) -> bool {
+        if self.placed.len() == self.oriented.len() {
+            return true;
+        }
+        for tile in 0..self.oriented.len() {
+            if self.used[tile] {
+                continue;
+            }
+            for orientation in 0..self.oriented[tile].len() {
+                if !self.fits(&self.oriented[tile][orientation].1) {
+                    continue;
+                }
+                self.used[tile] = true;
+                self.placed.push((tile, orientation));
+                if self.solve() {
+                    return true;
+                }
+                self.placed.pop();
+                self.used[tile] = false;
+            }
+        }
+        false
+    }
+
+    // Joins the placed tiles without their borders.
+    fn image(&self) -> Grid {
+        let mut image = Vec::new();
+        for tile_row in self.placed.chunks(self.side) {
+            let grids: Vec<&Grid> = tile_row
+                .iter()
+                .map(|(t, o)| &self.oriented[*t][*o].0)
+                .collect();
+            for r in 1..grids[0].len() - 1 {
+                image.push(
+                    grids
+                        .iter()
+                        .flat_map(|g| g[r][1..g[r].len() - 1].to_vec())
+                        .collect(),
+                );
+            }
+        }
+        image
+    }
+}
+
+fn assemble(tiles: &[Tile]) -> Option<Grid> {
+    let mut assembly = Assembly::new(tiles)?;
+    if assembly.solve() {
+        Some(assembly.image())
+    } else {
+        None
+    }
+}
+
+// Offsets of every '#' in the pattern.
+fn parse_pattern(pattern: &[&str]) -> Vec<(usize, usize)> {
+    pattern
+        .iter()
+        .enumerate()
+        .flat_map(|(r, line)| {
+            line.chars()
+                .enumerate()
+                .filter(|(_, c)| *c == '#')
+                .map(move |(c, _)| (r, c))
+        })
+        .collect()
+}
+
+// Cells covered by the pattern for every place it appears in the grid.
+fn find_pattern(grid: &[Vec<bool>], pattern: &[(usize, usize)]) -> HashSet<(usize, usize)> {
+    let height = pattern.iter().map(|(r, _)| r + 1).max().unwrap_or(0);
+    let width = pattern.iter().map(|(_, c)| c + 1).max().unwrap_or(0);
+    let mut cells = HashSet::new();
+    if grid.len() < height || grid[0].len() < width {
+        return cells;
+    }
+    for r in 0..=grid.len() - height {
+        for c in 0..=grid[0].len() - width {
+            if pattern.iter().all(|(pr, pc)| grid[r + pr][c + pc]) {
+                cells.extend(pattern.iter().map(|(pr, pc)| (r + pr, c + pc)));
+            }
+        }
+    }
+    cells
+}
+
+pub fn corners_product(input: Vec<String>) -> u64 {
+    find_corners(&create_tiles(input)).into_iter().product()
+}
+
+/// Count of '#' not covered by the pattern, in the orientation where it appears the most.
+pub fn water_roughness(input: Vec<String>, pattern: &[&str]) -> Option<usize> {
+    let image = assemble(&create_tiles(input))?;
+    let pattern = parse_pattern(pattern);
+    let total = image.iter().flatten().filter(|c| **c).count();
+    orientations(&image)
+        .iter()
+        .map(|g| find_pattern(g, &pattern).len())
+        .max()
+        .map(|m| total - m)
+}
+
+/// The assembled image without borders, one line per row.
+#[allow(dead_code)]
+pub fn assembled_image(input: Vec<String>) -> Option<String> {
+    assemble(&create_tiles(input)).map(|image| render(&image))
+}
+
+#[cfg(test)]
+fn example_tiles() -> Vec<String> {
+    vec![
+        "Tile 2522:",
+        "#...##.#.#",
+        ".........#",
+        "####.#.#..",
+        ".##.#.....",
+        "#.........",
+        "#.#.###.#.",
+        "###.#.#...",
+        ".#..##..#.",
+        "#...#.....",
+        "..#..##...",
+        "",
+        "Tile 1610:",
+        "##.###.#.#",
+        ".##...#.##",
+        ".#..#....#",
+        "....#..##.",
+        "######..##",
+        "..##...##.",
+        "#..##..#.#",
+        "...#..##..",
+        "..#.#....#",
+        "..###.###.",
+        "",
+        "Tile 3020:",
+        "..#..#.###",
+        "...###.#.#",
+        ".#.####...",
+        ".##.#..###",
+        ".........#",
+        ".###.#...#",
+        "......#...",
+        "...#...#.#",
+        "#......##.",
+        "#####.####",
+        "",
+        "Tile 3118:",
+        "#.#...#.#.",
+        "........##",
+        "....####..",
+        "##.#.....#",
+        "#.......##",
+        "...#.....#",
+        "...###.##.",
+        "##.#......",
+        ".##..#..#.",
+        "###.#.#.#.",
+        "",
+        "Tile 1434:",
+        "#.##....##",
+        ".....##.#.",
+        "#....#..##",
+        "#...#...#.",
+        "..#.######",
+        ".....#.###",
+        "#..##.....",
+        ".##.....#.",
+        ".###..#.#.",
+        ".#...###.#",
+        "",
+        "Tile 1182:",
+        "#..##..#.#",
+        "...#.#..##",
+        ".#....#..#",
+        "...#....##",
+        "#....#.#..",
+        "#.#.....##",
+        "#.#...#..#",
+        ".......#.#",
+        ".#...#.#.#",
+        "#.##....##",
+        "",
+        "Tile 2532:",
+        "##.#.#..##",
+        "..##......",
+        "#..####.#.",
+        "#.###....#",
+        "...##..#.#",
+        "..#.###.#.",
+        "#.##......",
+        ".#....####",
+        ".......#.#",
+        ".#...#...#",
+        "",
+        "Tile 2008:",
+        "#..###...#",
+        "##..####.#",
+        ".#..#.#..#",
+        "#.####.#.#",
+        "..##......",
+        "####.##..#",
+        ".#........",
+        "..###....#",
+        "##.##..###",
+        "##.##.#..#",
+        "",
+        "Tile 2648:",
+        "###.##..##",
+        "##.#.....#",
+        "....#..#.#",
+        "..#.......",
+        "..#.....##",
+        "#....#....",
+        "#..#..##.#",
+        "#...#.#..#",
+        ".##......#",
+        ".#.#...#.#",
+    ]
+    .into_iter()
+    .map(|s| s.to_string())
+    .collect()
+}
+
+#[test]
+fn test_orientations() {
+    let grid = parse_grid(&["#.".to_string(), "..".to_string()]);
+    assert_eq!(
+        parse_grid(&[".#".to_string(), "..".to_string()]),
+        rotate(&grid)
+    );
+    assert_eq!(
+        parse_grid(&[".#".to_string(), "..".to_string()]),
+        flip(&grid)
+    );
+    let grid = parse_grid(&["##.".to_string(), "...".to_string(), "...".to_string()]);
+    let all = orientations(&grid);
+    assert_eq!(8, all.len());
+    assert_eq!(8, all.iter().collect::<HashSet<&Grid>>().len());
+    assert_eq!(".##\n...\n...", render(&all[4]));
+}
+
+#[test]
+fn test_edges() {
+    let grid = parse_grid(&["##.".to_string(), "..#".to_string(), "#..".to_string()]);
+    assert_eq!([0b110, 0b010, 0b100, 0b101], edges(&grid));
+}
+
+#[test]
+fn test_create_tiles() {
+    let tiles = create_tiles(example_tiles());
+    assert_eq!(9, tiles.len());
+    assert_eq!(2522, tiles[0].id);
+    assert_eq!(10, tiles[0].grid.len());
+    assert_eq!("#...##.#.#", render(&tiles[0].grid[..1]));
+}
+
+#[test]
+fn test_corners_product() {
+    assert_eq!(27224044149120, corners_product(example_tiles()))
+}
+
+#[test]
+fn test_assembled_image() {
+    let image = assembled_image(example_tiles()).unwrap();
+    assert_eq!(24, image.lines().count());
+    assert!(image.lines().all(|l| l.len() == 24));
+    assert_eq!(None, assembled_image(example_tiles()[..22].to_vec()));
+}
+
+#[test]
+fn test_find_pattern() {
+    let grid = parse_grid(&[
+        ".#..................#.".to_string(),
+        "..#....##....##....###".to_string(),
+        "...#..#..#..#..#..#...".to_string(),
+    ]);
+    let pattern = parse_pattern(&SEA_MONSTER);
+    assert_eq!(15, pattern.len());
+    assert_eq!(15, find_pattern(&grid, &pattern).len());
+    assert_eq!(0, find_pattern(&flip(&grid), &pattern).len());
+}
+
+#[test]
+fn test_water_roughness() {
+    assert_eq!(Some(189), water_roughness(example_tiles(), &SEA_MONSTER));
+    assert_eq!(Some(0), water_roughness(example_tiles(), &["#"]));
+}
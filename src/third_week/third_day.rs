@@ -0,0 +1,169 @@
+use std::collections::{HashMap, HashSet};
+
+type Cubes<const N: usize> = HashSet<[i32; N]>;
+
+fn create_cubes<const N: usize>(input: Vec<String>) -> Cubes<N> {
+    input
+        .iter()
+        .enumerate()
+        .flat_map(|(y, line)| {
+            line.chars()
+                .enumerate()
+                .filter(|(_, c)| *c == '#')
+                .map(move |(x, _)| {
+                    let mut cell = [0; N];
+                    cell[0] = x as i32;
+                    cell[1] = y as i32;
+                    cell
+                })
+        })
+        .collect()
+}
+
+// Every offset in {-1, 0, 1}^N except the origin.
+fn offsets<const N: usize>() -> Vec<[i32; N]> {
+    (0..3_usize.pow(N as u32))
+        .map(|mut n| {
+            let mut offset = [0; N];
+            for o in offset.iter_mut() {
+                *o = (n % 3) as i32 - 1;
+                n /= 3;
+            }
+            offset
+        })
+        .filter(|o| o.iter().any(|v| *v != 0))
+        .collect()
+}
+
+fn cycle<const N: usize>(active: &Cubes<N>, offsets: &[[i32; N]]) -> Cubes<N> {
+    let mut counts: HashMap<[i32; N], usize> = HashMap::new();
+    for cell in active {
+        for offset in offsets {
+            let mut neighbour = *cell;
+            neighbour
+                .iter_mut()
+                .zip(offset.iter())
+                .for_each(|(n, o)| *n += o);
+            *counts.entry(neighbour).or_insert(0) += 1;
+        }
+    }
+    counts
+        .into_iter()
+        .filter(|(cell, count)| *count == 3 || (*count == 2 && active.contains(cell)))
+        .map(|(cell, _)| cell)
+        .collect()
+}
+
+fn bounds<const N: usize>(active: &Cubes<N>) -> ([i32; N], [i32; N]) {
+    let mut min = [i32::MAX; N];
+    let mut max = [i32::MIN; N];
+    for cell in active {
+        for d in 0..N {
+            min[d] = min[d].min(cell[d]);
+            max[d] = max[d].max(cell[d]);
+        }
+    }
+    (min, max)
+}
+
+/// Renders every z/w layer of the active cubes the way the puzzle statement does.
+#[allow(dead_code)]
+fn render_slices<const N: usize>(active: &Cubes<N>) -> String {
+    if active.is_empty() {
+        return String::new();
+    }
+    let names = ["x", "y", "z", "w"];
+    let (min, max) = bounds(active);
+    let layers = (2..N).fold(vec![Vec::new()], |layers, d| {
+        (min[d]..=max[d])
+            .flat_map(|v| {
+                layers.iter().map(move |l: &Vec<i32>| {
+                    let mut l = l.clone();
+                    l.push(v);
+                    l
+                })
+            })
+            .collect()
+    });
+    layers
+        .into_iter()
+        .map(|layer| {
+            let header = layer
+                .iter()
+                .enumerate()
+                .map(|(i, v)| {
+                    let name = names.get(i + 2).map(|n| n.to_string());
+                    format!("{}={}", name.unwrap_or(format!("d{}", i + 2)), v)
+                })
+                .collect::<Vec<String>>()
+                .join(", ");
+            let rows = (min[1]..=max[1])
+                .map(|y| {
+                    (min[0]..=max[0])
+                        .map(|x| {
+                            let mut cell = [0; N];
+                            cell[0] = x;
+                            cell[1] = y;
+                            cell[2..].copy_from_slice(&layer);
+                            if active.contains(&cell) {
+                                '#'
+                            } else {
+                                '.'
+                            }
+                        })
+                        .collect::<String>()
+                })
+                .collect::<Vec<String>>()
+                .join("\n");
+            format!("{}\n{}\n", header, rows)
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+pub fn active_cubes<const N: usize>(input: Vec<String>, cycles: usize) -> usize {
+    let offsets = offsets::<N>();
+    (0..cycles)
+        .fold(create_cubes::<N>(input), |active, _| {
+            cycle(&active, &offsets)
+        })
+        .len()
+}
+
+#[test]
+fn test_create_cubes() {
+    let input = vec![".#.".to_string(), "..#".to_string(), "###".to_string()];
+    let cubes = create_cubes::<3>(input);
+    assert_eq!(5, cubes.len());
+    assert!(cubes.contains(&[1, 0, 0]));
+    assert!(cubes.contains(&[2, 2, 0]));
+    assert!(!cubes.contains(&[0, 0, 0]));
+}
+
+#[test]
+fn test_offsets() {
+    assert_eq!(8, offsets::<2>().len());
+    assert_eq!(26, offsets::<3>().len());
+    assert_eq!(80, offsets::<4>().len());
+}
+
+#[test]
+fn test_render_slices() {
+    let input = vec![".#.".to_string(), "..#".to_string(), "###".to_string()];
+    let active = cycle(&create_cubes::<3>(input), &offsets());
+    let expected = vec![
+        "z=-1", "#..", "..#", ".#.", "", "z=0", "#.#", ".##", ".#.", "", "z=1", "#..", "..#",
+        ".#.", "",
+    ];
+    assert_eq!(expected.join("\n"), render_slices(&active));
+    let input = vec!["#.".to_string()];
+    let active = create_cubes::<4>(input);
+    assert_eq!("z=0, w=0\n#\n", render_slices(&active));
+}
+
+#[test]
+fn test_active_cubes() {
+    let input = vec![".#.".to_string(), "..#".to_string(), "###".to_string()];
+    assert_eq!(112, active_cubes::<3>(input.clone(), 6));
+    assert_eq!(848, active_cubes::<4>(input, 6));
+}